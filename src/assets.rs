@@ -0,0 +1,39 @@
+use std::{collections::HashMap, error::Error, fmt};
+use ggez::{graphics::Image, Context, GameError};
+use crate::entities::Sprite;
+
+//AN IMAGE THAT COULD NOT BE LOADED FROM `resources/`
+#[derive(Debug)]
+pub struct AssetError {
+    pub path: &'static str,
+    pub source: GameError
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not load the image \"resources{}\": {}", self.path, self.source)
+    }
+}
+
+impl Error for AssetError {}
+
+// Every texture of the game, loaded once at startup and looked up by sprite
+pub struct Assets {
+    images: HashMap<Sprite, Image>
+}
+
+impl Assets {
+    pub fn load(ctx: &mut Context) -> Result<Assets, AssetError> {
+        let mut images = HashMap::new();
+        for sprite in Sprite::ALL {
+            let image = Image::from_path(ctx, sprite.path()).map_err(|source| AssetError { path: sprite.path(), source })?;
+            images.insert(sprite, image);
+        }
+        Ok(Assets { images })
+    }
+
+    // The texture of a sprite, shared by every entity drawn with it
+    pub fn image(&self, sprite: Sprite) -> &Image {
+        &self.images[&sprite]
+    }
+}
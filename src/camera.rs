@@ -0,0 +1,112 @@
+//SCREEN SIZE
+pub const SCREEN_WIDTH: f64 = 640.0;
+pub const SCREEN_HEIGHT: f64 = 480.0;
+
+use crate::config::CameraConfig;
+
+// The scroll offset of the view, shared by the physics and the renderer
+#[derive(Copy, Clone, Debug)]
+pub struct Camera {
+    pub x: f64,
+    pub y: f64,
+    pub dead_zone_left: f64,
+    pub dead_zone_right: f64,
+    pub dead_zone_top: f64,
+    pub dead_zone_bottom: f64,
+    pub follow_rate: f64,
+    pub min_x: Option<f64>,
+    pub max_x: Option<f64>,
+    pub min_y: Option<f64>,
+    pub max_y: Option<f64>
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Camera {
+    // Camera at the start of the level, it never scrolls left of it nor below the ground line
+    pub fn new() -> Self {
+        Self::with_config(&CameraConfig::default())
+    }
+
+    pub fn with_config(config: &CameraConfig) -> Self {
+        let mut camera = Camera { x: 0.0, y: 0.0, dead_zone_left: 0.0, dead_zone_right: 0.0, dead_zone_top: 0.0, dead_zone_bottom: 0.0, follow_rate: 0.0, min_x: Some(0.0), max_x: None, min_y: None, max_y: Some(0.0) };
+        camera.configure(config);
+        camera
+    }
+
+    // Take the dead zone and follow rate from the tuning file, keeping the position and limits
+    pub fn configure(&mut self, config: &CameraConfig) {
+        self.dead_zone_left = config.dead_zone_left;
+        self.dead_zone_right = config.dead_zone_right;
+        self.dead_zone_top = config.dead_zone_top;
+        self.dead_zone_bottom = config.dead_zone_bottom;
+        self.follow_rate = config.follow_rate;
+    }
+
+    // Scroll so that the target point stays inside the dead zone
+    pub fn follow(&mut self, target_x: f64, target_y: f64, dt: f64) {
+        let (goal_x, goal_y) = self.goal(target_x, target_y);
+        let blend = if self.follow_rate > 0.0 { 1.0 - (-self.follow_rate * dt).exp() } else { 1.0 };
+        self.x += (goal_x - self.x) * blend;
+        self.y += (goal_y - self.y) * blend;
+        self.clamp();
+    }
+
+    // Jump straight to where following the target would settle, such as onto the spawn of a run
+    pub fn snap_to(&mut self, target_x: f64, target_y: f64) {
+        (self.x, self.y) = self.goal(target_x, target_y);
+        self.clamp();
+    }
+
+    // The closest position that has the target point inside the dead zone
+    fn goal(&self, target_x: f64, target_y: f64) -> (f64, f64) {
+        let (screen_x, screen_y) = self.to_screen(target_x, target_y);
+
+        let mut goal_x = self.x;
+        if screen_x > self.dead_zone_right {
+            goal_x = target_x - self.dead_zone_right;
+        } else if screen_x < self.dead_zone_left {
+            goal_x = target_x - self.dead_zone_left;
+        }
+
+        let mut goal_y = self.y;
+        if screen_y > self.dead_zone_bottom {
+            goal_y = target_y - self.dead_zone_bottom;
+        } else if screen_y < self.dead_zone_top {
+            goal_y = target_y - self.dead_zone_top;
+        }
+        (goal_x, goal_y)
+    }
+
+    fn clamp(&mut self) {
+        if let Some(min_x) = self.min_x { self.x = self.x.max(min_x); }
+        if let Some(max_x) = self.max_x { self.x = self.x.min(max_x); }
+        if let Some(min_y) = self.min_y { self.y = self.y.max(min_y); }
+        if let Some(max_y) = self.max_y { self.y = self.y.min(max_y); }
+    }
+
+    // Move the view by hand, still kept within its limits
+    pub fn scroll(&mut self, dx: f64, dy: f64) {
+        self.x += dx;
+        self.y += dy;
+        self.clamp();
+    }
+
+    pub fn to_screen(self, x: f64, y: f64) -> (f64, f64) {
+        (x - self.x, y - self.y)
+    }
+
+    // Where a point of the screen, such as the mouse, is in the world
+    pub fn to_world(self, x: f64, y: f64) -> (f64, f64) {
+        (x + self.x, y + self.y)
+    }
+
+    // Whether a horizontal span of the world overlaps the view
+    pub fn is_visible(&self, x: f64, width: f64) -> bool {
+        x + width > self.x && x < self.x + SCREEN_WIDTH
+    }
+}
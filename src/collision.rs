@@ -0,0 +1,280 @@
+use collider::{Collider, HbEvent, HbId, HbProfile, HbVel};
+use collider::geom::{v2, Card, CardMask, PlacedShape, Shape};
+
+//THE ID OF THE MOVING BOX, THE OBSTACLES ARE NUMBERED FROM 1 IN THE ORDER THEY ARE GIVEN
+const MOVER: HbId = 0;
+//SIZE OF THE CELLS COLLIDER SORTS THE BOXES INTO, ONE BLOCK
+const CELL_WIDTH: f64 = 128.0;
+//HOW FAR APART TWO TOUCHING BOXES HAVE TO DRIFT BEFORE THEY COUNT AS SEPARATED
+const PADDING: f64 = 0.01;
+//HOW MUCH DEEPER A TOUCH MAY BE INTO THE TOP OR BOTTOM THAN INTO THE SIDE AND STILL COUNT AS A FLOOR OR CEILING, ABSORBS ROUNDING AT BLOCK SEAMS
+const CORNER_TOLERANCE: f64 = 1e-6;
+
+//AXIS ALIGNED BOUNDING BOX, USED FOR COLLISION DETECTION
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rectangle {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64
+}
+
+//THE SIDE OF THE MOVING BOX THAT TOUCHED AN OBSTACLE
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Contact {
+    Floor,
+    Ceiling,
+    Left,
+    Right
+}
+
+//EVERY SIDE THAT TOUCHED SOMETHING DURING A MOVE
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Contacts {
+    pub floor: bool,
+    pub ceiling: bool,
+    pub left: bool,
+    pub right: bool
+}
+
+impl Contacts {
+    fn add(&mut self, contact: Contact) {
+        match contact {
+            Contact::Floor => self.floor = true,
+            Contact::Ceiling => self.ceiling = true,
+            Contact::Left => self.left = true,
+            Contact::Right => self.right = true,
+        }
+    }
+}
+
+//WHERE A MOVING BOX ENDED UP AND WHAT IT TOUCHED ON THE WAY
+#[derive(Debug, Copy, Clone)]
+pub struct Motion {
+    pub rect: Rectangle,
+    pub contacts: Contacts,
+    // The position of the obstacle the box ended up standing on, in the order the obstacles were given
+    pub ground: Option<usize>
+}
+
+// A box handed to collider, only the moving box is checked against the others
+#[derive(Debug, Copy, Clone)]
+struct Body {
+    id: HbId
+}
+
+impl HbProfile for Body {
+    fn id(&self) -> HbId {
+        self.id
+    }
+
+    fn can_interact(&self, other: &Body) -> bool {
+        self.id == MOVER || other.id == MOVER
+    }
+
+    fn cell_width() -> f64 {
+        CELL_WIDTH
+    }
+
+    fn padding() -> f64 {
+        PADDING
+    }
+}
+
+//RECTANGLE CLASS, USED FOR COLLISION DETECTION
+impl Rectangle {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Rectangle { x, y, width, height }
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    // Check if two rectangles intersect (collide)
+    pub fn intersects(&self, other: &Rectangle) -> bool {
+        self.x < other.right() &&
+        self.right() > other.x &&
+        self.y < other.bottom() &&
+        self.bottom() > other.y
+    }
+
+    // The same box as a collider shape, which is placed by its centre
+    fn shape(&self) -> PlacedShape {
+        Shape::rect(v2(self.width, self.height)).place(v2(self.x + self.width / 2.0, self.y + self.height / 2.0))
+    }
+
+    fn from_shape(shape: &PlacedShape) -> Rectangle {
+        Rectangle::new(shape.min_x(), shape.min_y(), shape.dims().x, shape.dims().y)
+    }
+
+    // The side of the box that touches or overlaps `other`, landing exactly on a corner counts as landing on the floor so seams between blocks never snag
+    fn contact(&self, other: &Rectangle) -> (Contact, f64) {
+        let (shape, other) = (self.shape(), other.shape());
+        let vertical = shape.masked_normal_from(&other, Self::mask(&[Card::MinusY, Card::PlusY]));
+        let horizontal = shape.masked_normal_from(&other, Self::mask(&[Card::MinusX, Card::PlusX]));
+        if vertical.len() <= horizontal.len() + CORNER_TOLERANCE {
+            let contact = if vertical.dir().y < 0.0 { Contact::Floor } else { Contact::Ceiling };
+            (contact, vertical.len())
+        } else {
+            let contact = if horizontal.dir().x > 0.0 { Contact::Left } else { Contact::Right };
+            (contact, horizontal.len())
+        }
+    }
+
+    fn mask(cards: &[Card]) -> CardMask {
+        let mut mask = CardMask::empty();
+        for card in cards {
+            mask[*card] = true;
+        }
+        mask
+    }
+
+    // Put the box flush against the side of `other` it touches
+    fn settle(&mut self, contact: Contact, other: &Rectangle) {
+        match contact {
+            Contact::Floor => self.y = other.y - self.height,
+            Contact::Ceiling => self.y = other.bottom(),
+            Contact::Left => self.x = other.right(),
+            Contact::Right => self.x = other.x - self.width,
+        }
+    }
+
+    // Move the box by (dx, dy), stopping at the obstacles in the way and sliding along their surfaces
+    pub fn move_and_collide<'a>(&self, dx: f64, dy: f64, obstacles: impl Iterator<Item = &'a Rectangle> + Clone) -> Motion {
+        let mut rect = *self;
+        let mut contacts = Contacts::default();
+        let mut ground = None;
+
+        //A BOX STARTING INSIDE AN OBSTACLE IS FIRST PUSHED OUT ALONG THE SHALLOWEST SIDE
+        for (index, obstacle) in obstacles.clone().enumerate() {
+            if !rect.intersects(obstacle) {
+                continue;
+            }
+            let (contact, depth) = rect.contact(obstacle);
+            if depth > 0.0 {
+                rect.settle(contact, obstacle);
+                contacts.add(contact);
+                if contact == Contact::Floor {
+                    ground = Some(index);
+                }
+            }
+        }
+
+        //ONLY THE OBSTACLES WITHIN REACH OF THE MOVE ARE HANDED TO COLLIDER, THE MOVE TAKES ONE UNIT OF ITS TIME
+        let reach = Rectangle::new(rect.x.min(rect.x + dx) - PADDING, rect.y.min(rect.y + dy) - PADDING, rect.width + dx.abs() + 2.0 * PADDING, rect.height + dy.abs() + 2.0 * PADDING);
+        let nearby: Vec<(usize, &Rectangle)> = obstacles.enumerate().filter(|(_, obstacle)| reach.intersects(obstacle)).collect();
+        let mut collider: Collider<Body> = Collider::new();
+        for (index, obstacle) in nearby.iter() {
+            collider.add_hitbox(Body { id: *index as HbId + 1 }, obstacle.shape().still());
+        }
+
+        let mut velocity = v2(dx, dy);
+        let mut resting = vec![];
+        let mut touched = collider.add_hitbox(Body { id: MOVER }, rect.shape().moving(velocity));
+        loop {
+            //EVERY OBSTACLE MET STOPS THE MOVE INTO IT, WHAT IS LEFT OF THE MOVE SLIDES ALONG ITS SURFACE
+            for body in touched.drain(..) {
+                let Some((index, obstacle)) = nearby.iter().find(|(index, _)| *index as HbId + 1 == body.id) else {
+                    continue;
+                };
+                let here = Rectangle::from_shape(&collider.get_hitbox(MOVER).value);
+                let (contact, _) = here.contact(obstacle);
+                let blocks = match contact {
+                    Contact::Floor => velocity.y > 0.0,
+                    Contact::Ceiling => velocity.y < 0.0,
+                    Contact::Left => velocity.x < 0.0,
+                    Contact::Right => velocity.x > 0.0,
+                };
+                if !blocks {
+                    continue;
+                }
+                match contact {
+                    Contact::Floor | Contact::Ceiling => velocity.y = 0.0,
+                    Contact::Left | Contact::Right => velocity.x = 0.0,
+                }
+                contacts.add(contact);
+                if contact == Contact::Floor {
+                    ground = Some(*index);
+                }
+                resting.push((contact, **obstacle));
+            }
+            collider.set_hitbox_vel(MOVER, HbVel::moving(velocity));
+
+            let time = collider.next_time();
+            if time > 1.0 {
+                break;
+            }
+            collider.set_time(time);
+            while let Some((event, first, second)) = collider.next() {
+                if event == HbEvent::Collide {
+                    touched.push(if first.id == MOVER { second } else { first });
+                }
+            }
+        }
+        collider.set_time(1.0);
+
+        //THE STOPS ARE SNAPPED FLUSH SO ROUNDING NEVER LEAVES THE BOX A HAIR INSIDE OR ABOVE WHAT IT STOPPED AT
+        rect = Rectangle::from_shape(&collider.get_hitbox(MOVER).value);
+        for (contact, obstacle) in resting {
+            rect.settle(contact, &obstacle);
+        }
+        Motion { rect, contacts, ground }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falling_box_lands_on_top_of_a_block() {
+        let block = Rectangle::new(0.0, 320.0, 128.0, 128.0);
+        let motion = Rectangle::new(10.0, 250.0, 42.0, 64.0).move_and_collide(0.0, 50.0, [block].iter());
+        assert_eq!(motion.rect.bottom(), 320.0);
+        assert!(motion.contacts.floor);
+        assert_eq!(motion.ground, Some(0));
+    }
+
+    #[test]
+    fn fast_move_stops_at_a_thin_block_instead_of_tunnelling() {
+        let block = Rectangle::new(300.0, 0.0, 8.0, 128.0);
+        let motion = Rectangle::new(0.0, 32.0, 42.0, 64.0).move_and_collide(5000.0, 0.0, [block].iter());
+        assert_eq!(motion.rect.right(), 300.0);
+        assert!(motion.contacts.right);
+    }
+
+    #[test]
+    fn running_over_a_seam_between_blocks_does_not_snag() {
+        let blocks = [Rectangle::new(0.0, 320.0, 128.0, 128.0), Rectangle::new(128.0, 320.0, 128.0, 128.0)];
+        let motion = Rectangle::new(100.0, 256.0, 42.0, 64.0).move_and_collide(60.0, 5.0, blocks.iter());
+        assert_eq!(motion.rect.x, 160.0);
+        assert_eq!(motion.rect.bottom(), 320.0);
+        assert!(!motion.contacts.right);
+    }
+
+    #[test]
+    fn seam_at_a_fractional_height_does_not_snag_either() {
+        let ground_y = 704.0 / 3.0;
+        let blocks = [Rectangle::new(256.0, ground_y, 128.0, 128.0), Rectangle::new(384.0, ground_y, 128.0, 128.0)];
+        let mut rect = Rectangle::new(386.0, ground_y - 64.0, 64.0, 64.0);
+        for _ in 0..8 {
+            let motion = rect.move_and_collide(-0.5, 0.1, blocks.iter());
+            assert!(motion.contacts.floor && !motion.contacts.left);
+            rect = motion.rect;
+        }
+        assert!(rect.x < 384.0);
+    }
+
+    #[test]
+    fn box_starting_inside_a_block_is_pushed_out_on_top() {
+        let block = Rectangle::new(0.0, 320.0, 128.0, 128.0);
+        let motion = Rectangle::new(40.0, 300.0, 42.0, 64.0).move_and_collide(0.0, 0.0, [block].iter());
+        assert_eq!(motion.rect.bottom(), 320.0);
+        assert_eq!(motion.ground, Some(0));
+    }
+}
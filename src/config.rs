@@ -0,0 +1,517 @@
+use std::{error::Error, fmt, fs, io, path::{Path, PathBuf}, time::SystemTime};
+use serde::Deserialize;
+use crate::camera::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::entities::{Ability, EnemyKind, Material, Projectile, Weapon};
+
+//HOW OFTEN THE TUNING FILE IS CHECKED FOR CHANGES, IN SECONDS
+const RELOAD_INTERVAL: f64 = 0.5;
+
+// Every gameplay tuning value, read from `resources/config.toml`
+#[derive(Copy, Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub player: PlayerConfig,
+    pub wall: WallConfig,
+    pub camera: CameraConfig,
+    pub spawn: SpawnConfig,
+    pub bullet: BulletConfig,
+    pub enemy: EnemyConfig,
+    pub enemies: EnemiesConfig,
+    pub powerup: PowerupConfig,
+    pub surface: SurfaceConfig,
+    pub abilities: AbilitiesConfig,
+    pub generator: GeneratorConfig,
+    pub health: HealthConfig,
+    pub boss: BossConfig,
+    pub weapons: WeaponsConfig
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub walk_speed: f64,
+    pub running_rate: f64,
+    pub jump_speed: f64,
+    pub gravity: f64,
+    pub max_fall_speed: f64,
+    pub acceleration: f64,
+    pub deceleration: f64,
+    pub air_control: f64,
+    pub jump_cut: f64,
+    pub coyote_time: f64,
+    pub jump_buffer_time: f64
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        PlayerConfig { walk_speed: 160.0, running_rate: 2.5, jump_speed: 280.0, gravity: 420.0, max_fall_speed: 250.0, acceleration: 1600.0, deceleration: 2400.0, air_control: 0.6, jump_cut: 0.5, coyote_time: 0.1, jump_buffer_time: 0.12 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WallConfig {
+    pub start_x: f64,
+    pub speed: f64,
+    pub accelerated_speed: f64,
+    pub pushback_speed: f64,
+    pub acceleration_distance: f64,
+    pub eat_distance: f64
+}
+
+impl Default for WallConfig {
+    fn default() -> Self {
+        WallConfig { start_x: -1200.0, speed: 60.0, accelerated_speed: 1800.0, pushback_speed: 300.0, acceleration_distance: 1000.0, eat_distance: 600.0 }
+    }
+}
+
+// The part of the screen the player moves in without scrolling, in pixels from its top left corner
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraConfig {
+    pub follow_rate: f64,
+    pub dead_zone_left: f64,
+    pub dead_zone_right: f64,
+    pub dead_zone_top: f64,
+    pub dead_zone_bottom: f64
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        CameraConfig { follow_rate: 10.0, dead_zone_left: 200.0, dead_zone_right: 320.0, dead_zone_top: 64.0, dead_zone_bottom: 400.0 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpawnConfig {
+    pub background_spacing: f64,
+    pub powerup_spacing: f64,
+    pub enemy_spacing: f64,
+    pub weapon_spacing: f64,
+    pub ice_chance: f64,
+    pub sticky_chance: f64
+}
+
+impl Default for SpawnConfig {
+    fn default() -> Self {
+        SpawnConfig { background_spacing: 480.0, powerup_spacing: 2300.0, enemy_spacing: 3600.0, weapon_spacing: 5000.0, ice_chance: 0.08, sticky_chance: 0.08 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BulletConfig {
+    pub lifetime: f64,
+    pub hits_enemy_bullets: bool,
+    pub grenade_gravity: f64
+}
+
+impl Default for BulletConfig {
+    fn default() -> Self {
+        BulletConfig { lifetime: 2.0, hits_enemy_bullets: true, grenade_gravity: 900.0 }
+    }
+}
+
+// The stats of one weapon, every field has to be given
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponStats {
+    // Shots per second
+    pub fire_rate: f64,
+    pub projectile: Projectile,
+    pub speed: f64,
+    // Bullets fired by a shot, `spread` degrees apart
+    pub count: u32,
+    pub spread: f64,
+    // Enemies a bullet passes through before it stops
+    pub pierce: u32,
+    pub damage: u32,
+    // Shots a pickup is good for, 0 for no limit
+    pub ammo: u32
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeaponsConfig {
+    pub blaster: WeaponStats,
+    pub shotgun: WeaponStats,
+    pub rifle: WeaponStats,
+    pub launcher: WeaponStats
+}
+
+impl WeaponsConfig {
+    pub fn stats(&self, weapon: Weapon) -> &WeaponStats {
+        match weapon {
+            Weapon::Blaster => &self.blaster,
+            Weapon::Shotgun => &self.shotgun,
+            Weapon::Rifle => &self.rifle,
+            Weapon::Launcher => &self.launcher,
+        }
+    }
+}
+
+impl Default for WeaponsConfig {
+    fn default() -> Self {
+        WeaponsConfig {
+            blaster: WeaponStats { fire_rate: 6.0, projectile: Projectile::Bullet, speed: 800.0, count: 1, spread: 0.0, pierce: 0, damage: 1, ammo: 0 },
+            shotgun: WeaponStats { fire_rate: 1.5, projectile: Projectile::Bullet, speed: 700.0, count: 5, spread: 8.0, pierce: 0, damage: 1, ammo: 15 },
+            rifle: WeaponStats { fire_rate: 2.0, projectile: Projectile::Bullet, speed: 1400.0, count: 1, spread: 0.0, pierce: 3, damage: 2, ammo: 20 },
+            launcher: WeaponStats { fire_rate: 1.0, projectile: Projectile::Grenade, speed: 500.0, count: 1, spread: 0.0, pierce: 0, damage: 4, ammo: 8 }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemyConfig {
+    pub bullet_lifetime: f64,
+    pub charge_time: f64,
+    pub rest_time: f64,
+    pub bob_height: f64,
+    pub bob_rate: f64,
+    pub devil_jump_speed: f64
+}
+
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        EnemyConfig { bullet_lifetime: 8.0, charge_time: 1.0, rest_time: 1.5, bob_height: 24.0, bob_rate: 3.0, devil_jump_speed: 300.0 }
+    }
+}
+
+// The stats of one kind of enemy, every field has to be given
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyStats {
+    pub health: u32,
+    pub speed: f64,
+    // Distance the player has to cover between two shots, 0 for an enemy that never fires
+    pub fire_cooldown: f64,
+    // How close the player has to come before the enemy reacts, 0 to always react
+    pub sight: f64,
+    pub contact_damage: bool,
+    pub kill_score: u64,
+    // How the enemy fires, left out for enemies that never do
+    #[serde(default)]
+    pub pattern: BulletPattern
+}
+
+// How an attack is fired: `burst` shots, each of `count` bullets fanned out around the aim,
+// bullets with a `turn_rate` home in on the player and bullets with `gravity` are lobbed so they land on them
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BulletPattern {
+    pub speed: f64,
+    pub count: u32,
+    // Degrees between two bullets of a shot
+    pub spread: f64,
+    pub burst: u32,
+    // Seconds between two shots of a burst
+    pub burst_interval: f64,
+    // Degrees per second
+    pub turn_rate: f64,
+    pub gravity: f64
+}
+
+impl BulletPattern {
+    fn is_valid(&self) -> bool {
+        self.speed > 0.0 && self.count > 0 && self.burst > 0 && self.spread >= 0.0 && self.burst_interval >= 0.0 && self.turn_rate >= 0.0 && self.gravity >= 0.0
+    }
+}
+
+impl Default for BulletPattern {
+    fn default() -> Self {
+        BulletPattern { speed: 120.0, count: 1, spread: 15.0, burst: 1, burst_interval: 0.15, turn_rate: 0.0, gravity: 0.0 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemiesConfig {
+    pub turret: EnemyStats,
+    pub walker: EnemyStats,
+    pub flyer: EnemyStats,
+    pub charger: EnemyStats,
+    pub devil: EnemyStats
+}
+
+impl EnemiesConfig {
+    pub fn stats(&self, kind: EnemyKind) -> &EnemyStats {
+        match kind {
+            EnemyKind::Turret => &self.turret,
+            EnemyKind::Walker => &self.walker,
+            EnemyKind::Flyer => &self.flyer,
+            EnemyKind::Charger => &self.charger,
+            EnemyKind::Devil => &self.devil,
+        }
+    }
+}
+
+impl Default for EnemiesConfig {
+    fn default() -> Self {
+        EnemiesConfig {
+            turret: EnemyStats { health: 3, speed: 0.0, fire_cooldown: 300.0, sight: 0.0, contact_damage: false, kill_score: 100, pattern: BulletPattern { burst: 3, ..BulletPattern::default() } },
+            walker: EnemyStats { health: 2, speed: 60.0, fire_cooldown: 0.0, sight: 0.0, contact_damage: true, kill_score: 50, pattern: BulletPattern::default() },
+            flyer: EnemyStats { health: 1, speed: 90.0, fire_cooldown: 450.0, sight: 640.0, contact_damage: false, kill_score: 150, pattern: BulletPattern { speed: 260.0, gravity: 400.0, ..BulletPattern::default() } },
+            charger: EnemyStats { health: 4, speed: 360.0, fire_cooldown: 0.0, sight: 320.0, contact_damage: true, kill_score: 200, pattern: BulletPattern::default() },
+            devil: EnemyStats { health: 3, speed: 140.0, fire_cooldown: 0.0, sight: 480.0, contact_damage: true, kill_score: 250, pattern: BulletPattern::default() }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerupConfig {
+    pub slow_factor: f64,
+    pub slow_distance: f64
+}
+
+impl Default for PowerupConfig {
+    fn default() -> Self {
+        PowerupConfig { slow_factor: 0.25, slow_distance: 1000.0 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SurfaceConfig {
+    pub ice_grip: f64,
+    pub sticky_grip: f64,
+    pub sticky_speed: f64
+}
+
+impl Default for SurfaceConfig {
+    fn default() -> Self {
+        SurfaceConfig { ice_grip: 0.1, sticky_grip: 4.0, sticky_speed: 0.5 }
+    }
+}
+
+impl SurfaceConfig {
+    // How strongly the player speeds up and slows down on a material, relative to a normal block
+    pub fn grip(&self, material: Material) -> f64 {
+        match material {
+            Material::Normal => 1.0,
+            Material::Ice => self.ice_grip,
+            Material::Sticky => self.sticky_grip,
+        }
+    }
+
+    // The share of the full walking or running speed reachable on a material
+    pub fn speed(&self, material: Material) -> f64 {
+        match material {
+            Material::Normal | Material::Ice => 1.0,
+            Material::Sticky => self.sticky_speed,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AbilitiesConfig {
+    pub double_jump_distance: f64,
+    pub dash_distance: f64,
+    pub wall_jump_distance: f64,
+    pub dash_speed: f64,
+    pub dash_time: f64,
+    pub wall_slide_speed: f64,
+    pub wall_jump_push: f64
+}
+
+impl Default for AbilitiesConfig {
+    fn default() -> Self {
+        AbilitiesConfig { double_jump_distance: 3000.0, dash_distance: 8000.0, wall_jump_distance: 0.0, dash_speed: 700.0, dash_time: 0.15, wall_slide_speed: 80.0, wall_jump_push: 300.0 }
+    }
+}
+
+impl AbilitiesConfig {
+    // The distance the player has to cover before an ability unlocks
+    pub fn unlock_distance(&self, ability: Ability) -> f64 {
+        match ability {
+            Ability::DoubleJump => self.double_jump_distance,
+            Ability::Dash => self.dash_distance,
+            Ability::WallJump => self.wall_jump_distance,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneratorConfig {
+    pub ramp_distance: f64
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig { ramp_distance: 20000.0 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    pub max_health: u32,
+    pub lives: u32,
+    pub invulnerable_time: f64,
+    pub knockback_speed: f64,
+    pub knockback_lift: f64,
+    pub knockback_time: f64
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig { max_health: 3, lives: 3, invulnerable_time: 1.5, knockback_speed: 250.0, knockback_lift: 200.0, knockback_time: 0.25 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BossConfig {
+    pub first_distance: f64,
+    pub interval: f64,
+    pub health: u32,
+    pub phases: u32,
+    pub kill_score: u64,
+    pub fire_cooldown: f64,
+    // The attack of each phase, phases past the last pattern start over from the first
+    pub patterns: [BulletPattern; 3],
+    pub sway_speed: f64
+}
+
+impl Default for BossConfig {
+    fn default() -> Self {
+        BossConfig { first_distance: 10000.0, interval: 15000.0, health: 30, phases: 3, kill_score: 2000, fire_cooldown: 1.5, patterns: [
+            BulletPattern { speed: 200.0, burst: 3, ..BulletPattern::default() },
+            BulletPattern { speed: 200.0, count: 5, turn_rate: 30.0, ..BulletPattern::default() },
+            BulletPattern { speed: 200.0, count: 12, spread: 30.0, burst: 2, burst_interval: 0.4, ..BulletPattern::default() }
+        ], sway_speed: 120.0 }
+    }
+}
+
+//WHY THE TUNING FILE COULD NOT BE USED
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Invalid(&'static str)
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, error) => write!(f, "could not read {}: {}", path.display(), error),
+            ConfigError::Parse(path, error) => write!(f, "could not parse {}: {}", path.display(), error),
+            ConfigError::Invalid(reason) => write!(f, "invalid tuning value: {}", reason),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl GameConfig {
+    pub fn load(path: &Path) -> Result<GameConfig, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|error| ConfigError::Io(path.to_path_buf(), error))?;
+        let config: GameConfig = toml::from_str(&text).map_err(|error| ConfigError::Parse(path.to_path_buf(), error))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // Reject values the simulation cannot work with
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let checks = [
+            (self.player.walk_speed > 0.0, "player.walk_speed must be positive"),
+            (self.player.running_rate >= 1.0, "player.running_rate must be at least 1"),
+            (self.player.jump_speed > 0.0, "player.jump_speed must be positive"),
+            (self.player.gravity > 0.0, "player.gravity must be positive"),
+            (self.player.max_fall_speed > 0.0, "player.max_fall_speed must be positive"),
+            (self.player.acceleration > 0.0, "player.acceleration must be positive"),
+            (self.player.deceleration > 0.0, "player.deceleration must be positive"),
+            ((0.0..=1.0).contains(&self.player.air_control), "player.air_control must be between 0 and 1"),
+            ((0.0..=1.0).contains(&self.player.jump_cut), "player.jump_cut must be between 0 and 1"),
+            (self.player.coyote_time >= 0.0, "player.coyote_time must not be negative"),
+            (self.player.jump_buffer_time >= 0.0, "player.jump_buffer_time must not be negative"),
+            (self.wall.speed >= 0.0, "wall.speed must not be negative"),
+            (self.wall.accelerated_speed >= self.wall.speed, "wall.accelerated_speed must not be below wall.speed"),
+            (self.wall.pushback_speed >= 0.0, "wall.pushback_speed must not be negative"),
+            (self.wall.acceleration_distance > self.wall.eat_distance, "wall.acceleration_distance must be above wall.eat_distance"),
+            (self.wall.eat_distance > 0.0, "wall.eat_distance must be positive"),
+            (self.camera.follow_rate >= 0.0, "camera.follow_rate must not be negative"),
+            ((0.0..=SCREEN_WIDTH).contains(&self.camera.dead_zone_left) && (self.camera.dead_zone_left..=SCREEN_WIDTH).contains(&self.camera.dead_zone_right), "camera.dead_zone_left and camera.dead_zone_right must be on screen, left before right"),
+            ((0.0..=SCREEN_HEIGHT).contains(&self.camera.dead_zone_top) && (self.camera.dead_zone_top..=SCREEN_HEIGHT).contains(&self.camera.dead_zone_bottom), "camera.dead_zone_top and camera.dead_zone_bottom must be on screen, top above bottom"),
+            (self.spawn.background_spacing > 0.0, "spawn.background_spacing must be positive"),
+            (self.spawn.powerup_spacing > 0.0, "spawn.powerup_spacing must be positive"),
+            (self.spawn.enemy_spacing > 0.0, "spawn.enemy_spacing must be positive"),
+            (self.spawn.ice_chance >= 0.0 && self.spawn.sticky_chance >= 0.0 && self.spawn.ice_chance + self.spawn.sticky_chance <= 1.0, "spawn.ice_chance and spawn.sticky_chance must not be negative nor add up to more than 1"),
+            (self.spawn.weapon_spacing > 0.0, "spawn.weapon_spacing must be positive"),
+            (self.bullet.lifetime > 0.0, "bullet.lifetime must be positive"),
+            (self.bullet.grenade_gravity >= 0.0, "bullet.grenade_gravity must not be negative"),
+            (Weapon::ALL.iter().all(|weapon| { let stats = self.weapons.stats(*weapon); stats.fire_rate > 0.0 && stats.speed > 0.0 && stats.count > 0 && stats.damage > 0 && stats.spread >= 0.0 }), "weapons need a positive fire_rate, speed, count and damage, and a spread that is not negative"),
+            (self.weapons.blaster.ammo == 0, "weapons.blaster.ammo must be 0, it is the weapon the player falls back to"),
+            (self.enemy.bullet_lifetime > 0.0, "enemy.bullet_lifetime must be positive"),
+            (self.enemy.charge_time > 0.0 && self.enemy.rest_time >= 0.0, "enemy.charge_time must be positive and enemy.rest_time not negative"),
+            (self.enemy.bob_height >= 0.0 && self.enemy.bob_rate >= 0.0, "enemy.bob_height and enemy.bob_rate must not be negative"),
+            (self.enemy.devil_jump_speed > 0.0, "enemy.devil_jump_speed must be positive"),
+            (EnemyKind::ALL.iter().all(|kind| self.enemies.stats(*kind).health > 0), "enemies health must be positive"),
+            (EnemyKind::ALL.iter().all(|kind| { let stats = self.enemies.stats(*kind); stats.speed >= 0.0 && stats.fire_cooldown >= 0.0 && stats.sight >= 0.0 }), "enemies speed, fire_cooldown and sight must not be negative"),
+            (EnemyKind::ALL.iter().all(|kind| self.enemies.stats(*kind).pattern.is_valid()), "enemies patterns need a positive speed, count and burst, and nothing negative"),
+            (self.powerup.slow_factor > 0.0 && self.powerup.slow_factor < 1.0, "powerup.slow_factor must be between 0 and 1"),
+            (self.powerup.slow_distance > 0.0, "powerup.slow_distance must be positive"),
+            (self.surface.ice_grip > 0.0, "surface.ice_grip must be positive"),
+            (self.surface.sticky_grip > 0.0, "surface.sticky_grip must be positive"),
+            (self.surface.sticky_speed > 0.0 && self.surface.sticky_speed <= 1.0, "surface.sticky_speed must be above 0 and at most 1"),
+            (Ability::ALL.iter().all(|ability| self.abilities.unlock_distance(*ability) >= 0.0), "abilities unlock distances must not be negative"),
+            (self.abilities.dash_speed > 0.0, "abilities.dash_speed must be positive"),
+            (self.abilities.dash_time > 0.0, "abilities.dash_time must be positive"),
+            (self.abilities.wall_slide_speed > 0.0, "abilities.wall_slide_speed must be positive"),
+            (self.abilities.wall_jump_push >= 0.0, "abilities.wall_jump_push must not be negative"),
+            (self.generator.ramp_distance > 0.0, "generator.ramp_distance must be positive"),
+            (self.health.max_health > 0, "health.max_health must be positive"),
+            (self.health.lives > 0, "health.lives must be positive"),
+            (self.health.invulnerable_time >= 0.0, "health.invulnerable_time must not be negative"),
+            (self.health.knockback_speed >= 0.0 && self.health.knockback_lift >= 0.0, "health.knockback_speed and health.knockback_lift must not be negative"),
+            (self.health.knockback_time >= 0.0 && self.health.knockback_time <= self.health.invulnerable_time, "health.knockback_time must be between 0 and health.invulnerable_time"),
+            (self.boss.first_distance > 0.0 && self.boss.interval > 0.0, "boss.first_distance and boss.interval must be positive"),
+            (self.boss.phases > 0 && self.boss.health >= self.boss.phases, "boss.phases must be positive and boss.health at least boss.phases"),
+            (self.boss.fire_cooldown > 0.0, "boss.fire_cooldown must be positive"),
+            (self.boss.patterns.iter().all(BulletPattern::is_valid), "boss patterns need a positive speed, count and burst, and nothing negative"),
+            (self.boss.sway_speed >= 0.0, "boss.sway_speed must not be negative"),
+        ];
+        match checks.iter().find(|(ok, _)| !ok) {
+            Some((_, reason)) => Err(ConfigError::Invalid(reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+// Watches the tuning file and reloads it when it changes on disk
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    since_check: f64
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        let modified = Self::modified(&path);
+        ConfigWatcher { path, modified, since_check: 0.0 }
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    // The freshly loaded file, once it has changed since the last poll
+    pub fn poll(&mut self, dt: f64) -> Option<Result<GameConfig, ConfigError>> {
+        self.since_check += dt;
+        if self.since_check < RELOAD_INTERVAL {
+            return None;
+        }
+        self.since_check = 0.0;
+
+        let modified = Self::modified(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(GameConfig::load(&self.path))
+    }
+}
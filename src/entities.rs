@@ -0,0 +1,577 @@
+use std::f64::consts::PI;
+use serde::{Deserialize, Serialize};
+use crate::collision::Rectangle;
+
+//THE PLAYER'S HITBOX, RELATIVE TO THE TOP LEFT CORNER OF ITS SPRITE
+const PLAYER_HITBOX_OFFSET_X: f64 = 11.0;
+const PLAYER_HITBOX_WIDTH: f64 = 42.0;
+const PLAYER_HITBOX_HEIGHT: f64 = 64.0;
+
+//THE IMAGES AN ENTITY CAN BE DRAWN WITH, RESOLVED TO A TEXTURE BY THE RENDERER
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sprite {
+    PlayerRight,
+    PlayerLeft,
+    Block,
+    Wall,
+    Bullet,
+    Background1,
+    Background2,
+    Background3,
+    Powerup,
+    Enemy,
+    EnemyLeft,
+    DevilRight,
+    DevilLeft,
+    EnemyBullet,
+    Cutscene1,
+    Cutscene2,
+    Cutscene3,
+    Cutscene4,
+    Cutscene5
+}
+
+impl Sprite {
+    pub const ALL: [Sprite; 19] = [
+        Sprite::PlayerRight, Sprite::PlayerLeft, Sprite::Block, Sprite::Wall, Sprite::Bullet, Sprite::Background1,
+        Sprite::Background2, Sprite::Background3, Sprite::Powerup, Sprite::Enemy, Sprite::EnemyLeft, Sprite::DevilRight, Sprite::DevilLeft, Sprite::EnemyBullet,
+        Sprite::Cutscene1, Sprite::Cutscene2, Sprite::Cutscene3, Sprite::Cutscene4, Sprite::Cutscene5
+    ];
+
+    // The file in `resources/` holding the image
+    pub fn path(&self) -> &'static str {
+        match self {
+            Sprite::PlayerRight => "/player_right.png",
+            Sprite::PlayerLeft => "/player_left.png",
+            Sprite::Block => "/block_one.png",
+            Sprite::Wall => "/wall2.png",
+            Sprite::Bullet => "/bullet.png",
+            Sprite::Background1 => "/background1.png",
+            Sprite::Background2 => "/background2.png",
+            Sprite::Background3 => "/background3.png",
+            Sprite::Powerup => "/clock2.png",
+            Sprite::Enemy => "/enemy_right.png",
+            Sprite::EnemyLeft => "/enemy_left.png",
+            Sprite::DevilRight => "/devil_right.png",
+            Sprite::DevilLeft => "/devil_left.png",
+            Sprite::EnemyBullet => "/enemy_bullet.png",
+            Sprite::Cutscene1 => "/cutscene1.png",
+            Sprite::Cutscene2 => "/cutscene2.png",
+            Sprite::Cutscene3 => "/cutscene3.png",
+            Sprite::Cutscene4 => "/cutscene4.png",
+            Sprite::Cutscene5 => "/cutscene5.png",
+        }
+    }
+
+    // The sprite drawn from an image file such as `block_one.png`, used to match the images of a Tiled tileset
+    pub fn from_file_name(name: &str) -> Option<Sprite> {
+        Sprite::ALL.into_iter().find(|sprite| sprite.path().trim_start_matches('/') == name)
+    }
+}
+
+//WHAT A BLOCK IS MADE OF, CHANGES HOW MUCH GRIP THE PLAYER HAS ON TOP OF IT
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Material {
+    #[default]
+    Normal,
+    Ice,
+    Sticky
+}
+
+impl Material {
+    // The material written in lowercase, as in the level files
+    pub fn from_name(name: &str) -> Option<Material> {
+        match name.to_lowercase().as_str() {
+            "normal" => Some(Material::Normal),
+            "ice" => Some(Material::Ice),
+            "sticky" => Some(Material::Sticky),
+            _ => None,
+        }
+    }
+}
+
+//THE MOVES THE PLAYER CAN UNLOCK DURING A RUN
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Ability {
+    DoubleJump,
+    Dash,
+    WallJump
+}
+
+impl Ability {
+    pub const ALL: [Ability; 3] = [Ability::DoubleJump, Ability::Dash, Ability::WallJump];
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Ability::DoubleJump => "DOUBLE JUMP: PRESS Z IN THE AIR",
+            Ability::Dash => "AIR DASH: PRESS C IN THE AIR",
+            Ability::WallJump => "WALL JUMP: HOLD TOWARDS A WALL AND PRESS Z",
+        }
+    }
+}
+
+//THE KINDS OF ENEMIES, EACH WITH ITS OWN BEHAVIOUR AND ITS OWN STATS IN THE TUNING FILE
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnemyKind {
+    // Hangs in place and fires at the player, the original enemy
+    #[default]
+    Turret,
+    // Patrols the top of the blocks it stands on, turning around at edges and walls
+    Walker,
+    // Drifts towards the player through the air, bobbing up and down
+    Flyer,
+    // Waits on the ground until the player comes close, then rushes at them
+    Charger,
+    // Hunts the player along the ground, jumping up steps and across gaps
+    Devil
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 5] = [EnemyKind::Turret, EnemyKind::Walker, EnemyKind::Flyer, EnemyKind::Charger, EnemyKind::Devil];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyKind::Turret => "turret",
+            EnemyKind::Walker => "walker",
+            EnemyKind::Flyer => "flyer",
+            EnemyKind::Charger => "charger",
+            EnemyKind::Devil => "devil",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<EnemyKind> {
+        EnemyKind::ALL.into_iter().find(|kind| kind.name() == name.to_lowercase())
+    }
+
+    // The image and the rotation an enemy is drawn with, turrets hang upside down from their position
+    pub fn looks(&self, direction: f64) -> (Sprite, f32) {
+        match self {
+            EnemyKind::Turret => (Sprite::Enemy, std::f32::consts::PI),
+            EnemyKind::Devil if direction < 0.0 => (Sprite::DevilLeft, 0.0),
+            EnemyKind::Devil => (Sprite::DevilRight, 0.0),
+            _ if direction < 0.0 => (Sprite::EnemyLeft, 0.0),
+            _ => (Sprite::Enemy, 0.0),
+        }
+    }
+
+    // Whether the enemy moves along the ground under gravity
+    pub fn walks(&self) -> bool {
+        matches!(self, EnemyKind::Walker | EnemyKind::Charger | EnemyKind::Devil)
+    }
+}
+
+//WHAT AN ENEMY IS DOING, FOR THE ENEMIES THAT REACT TO THE PLAYER
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EnemyState {
+    Idle,
+    // Rushing at the player, with the time left
+    Charging(f64),
+    // Catching its breath after a charge, with the time left
+    Resting(f64)
+}
+
+//THE WEAPONS OF THE PLAYER, EACH WITH ITS OWN STATS IN THE TUNING FILE
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Weapon {
+    // Always at hand, the player goes back to it once the ammo of another weapon runs out
+    #[default]
+    Blaster,
+    // A fan of bullets
+    Shotgun,
+    // Fast bullets that go through enemies
+    Rifle,
+    // Heavy grenades that fall as they fly
+    Launcher
+}
+
+impl Weapon {
+    pub const ALL: [Weapon; 4] = [Weapon::Blaster, Weapon::Shotgun, Weapon::Rifle, Weapon::Launcher];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weapon::Blaster => "blaster",
+            Weapon::Shotgun => "shotgun",
+            Weapon::Rifle => "rifle",
+            Weapon::Launcher => "launcher",
+        }
+    }
+}
+
+//WHAT A WEAPON FIRES
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Projectile {
+    // Flies straight
+    Bullet,
+    // Falls under `bullet.grenade_gravity`
+    Grenade
+}
+
+//WHICH ABILITIES THE PLAYER HAS UNLOCKED
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Abilities {
+    pub double_jump: bool,
+    pub dash: bool,
+    pub wall_jump: bool
+}
+
+impl Abilities {
+    pub fn has(&self, ability: Ability) -> bool {
+        match ability {
+            Ability::DoubleJump => self.double_jump,
+            Ability::Dash => self.dash,
+            Ability::WallJump => self.wall_jump,
+        }
+    }
+
+    pub fn set(&mut self, ability: Ability, unlocked: bool) {
+        match ability {
+            Ability::DoubleJump => self.double_jump = unlocked,
+            Ability::Dash => self.dash = unlocked,
+            Ability::WallJump => self.wall_jump = unlocked,
+        }
+    }
+}
+
+//THE STATE OF PLAYER
+#[derive(Copy, Clone, Debug)]
+pub struct Player {
+    pub pos_x: f64,
+    pub pos_y: f64,
+    pub direction: f64,
+    pub standing: bool,
+    pub vertical_speed: f64,
+    pub horizontal_speed: f64,
+    pub collides_right: bool,
+    pub collides_left: bool,
+    pub jumping: bool,
+    pub coyote_timer: f64,
+    pub jump_buffer_timer: f64,
+    pub surface: Option<Material>,
+    pub abilities: Abilities,
+    pub air_jumps: u32,
+    pub can_dash: bool,
+    pub dash_timer: f64,
+    pub health: u32,
+    pub lives: u32,
+    pub invulnerable_timer: f64,
+    pub knockback_timer: f64,
+    // The last ground the player stood on, where a fall puts them back
+    pub checkpoint: (f64, f64),
+    pub weapon: Weapon,
+    // Shots left with the current weapon, unused by the blaster
+    pub ammo: u32,
+    // Seconds until the weapon can fire again
+    pub fire_timer: f64
+}
+
+// Implement methods for the Player struct
+impl Player {
+    // Constructor method to create a new Player instance
+    pub fn new(pos_x: f64, pos_y: f64, direction: f64, standing: bool, vertical_speed: f64, horizontal_speed: f64, collides_right: bool, collides_left: bool) -> Self {
+        Player {
+            pos_x,
+            pos_y,
+            direction,
+            standing,
+            vertical_speed,
+            horizontal_speed,
+            collides_right,
+            collides_left,
+            jumping: false,
+            coyote_timer: 0.0,
+            jump_buffer_timer: 0.0,
+            surface: None,
+            abilities: Abilities::default(),
+            air_jumps: 0,
+            can_dash: false,
+            dash_timer: 0.0,
+            health: 0,
+            lives: 0,
+            invulnerable_timer: 0.0,
+            knockback_timer: 0.0,
+            checkpoint: (pos_x, pos_y),
+            weapon: Weapon::Blaster,
+            ammo: 0,
+            fire_timer: 0.0
+        }
+    }
+
+    // Method to display player information
+    fn _describe(&self) {
+        println!("pos_x: {}", self.pos_x);
+        println!("pos_y: {}", self.pos_y);
+    }
+
+    // The box used for every collision of the player, with blocks, bullets and pickups alike
+    pub fn collider(&self) -> Rectangle {
+        Rectangle::new(self.pos_x + PLAYER_HITBOX_OFFSET_X, self.pos_y, PLAYER_HITBOX_WIDTH, PLAYER_HITBOX_HEIGHT)
+    }
+
+    // Place the player so that its hitbox sits at `collider`
+    pub fn move_to(&mut self, collider: &Rectangle) {
+        self.pos_x = collider.x - PLAYER_HITBOX_OFFSET_X;
+        self.pos_y = collider.y;
+    }
+}
+
+pub struct Background {
+    pub x: f64,
+    pub y: f64,
+    pub image: Sprite,
+    pub rotation: f64
+}
+
+// THE BASIC BUILDING BLOCK OF THE WORLD
+// Implement methods for the Background struct
+impl Background {
+    // Constructor method to create a new Background instance
+    pub fn new(x: f64, y: f64, image: Sprite, rotation: f64) -> Self {
+        Background {
+            x,
+            y,
+            image,
+            rotation
+        }
+    }
+}
+
+pub struct Block {
+    pub rect: Rectangle,
+    pub image: Sprite,
+    pub material: Material
+}
+
+// THE BASIC BUILDING BLOCK OF THE WORLD
+// Implement methods for the Block struct
+impl Block {
+    // Constructor method to create a new Block instance
+    pub fn new(rect: Rectangle, image: Sprite, material: Material) -> Self {
+        Block {
+            rect,
+            image,
+            material
+        }
+    }
+
+    // Method to display player information
+    fn _describe(&self) {
+        println!("pos_x: {}", self.rect.x);
+        println!("pos_y: {}", self.rect.y);
+    }
+}
+
+pub struct Bullet {
+    pub x: f64,
+    pub y: f64,
+    // In pixels per second
+    pub horizontal_speed: f64,
+    pub vertical_speed: f64,
+    pub gravity: f64,
+    pub projectile: Projectile,
+    pub damage: u32,
+    // Enemies it can still pass through
+    pub pierce: u32,
+    // Where it was before its last move, kept once it has gone through an enemy so it only hurts what it runs into afresh
+    pub previous: Option<Rectangle>,
+    pub lifetime: f64,
+    // Where it was before the current tick, drawing blends from there
+    pub last_x: f64,
+    pub last_y: f64
+}
+
+impl Bullet {
+    pub fn hitbox(&self) -> Rectangle {
+        Rectangle::new(self.x, self.y, 32.0, 32.0)
+    }
+
+    // Whether it has just run into `rect`, rather than still being inside something it already hit
+    pub fn enters(&self, rect: &Rectangle) -> bool {
+        self.hitbox().intersects(rect) && !self.previous.is_some_and(|previous| previous.intersects(rect))
+    }
+
+    // Go on through what it hit while it can still pierce, stop otherwise
+    pub fn spend(&mut self) {
+        if self.pierce > 0 {
+            self.pierce -= 1;
+            self.previous = Some(self.hitbox());
+        } else {
+            self.lifetime = 0.0;
+        }
+    }
+}
+
+pub struct Powerup {
+    pub x: f64,
+    pub y: f64,
+    pub image: Sprite
+}
+
+//A WEAPON LYING AROUND, PICKING IT UP FILLS ITS AMMO
+pub struct WeaponPickup {
+    pub x: f64,
+    pub y: f64,
+    pub weapon: Weapon
+}
+
+impl WeaponPickup {
+    pub fn hitbox(&self) -> Rectangle {
+        Rectangle::new(self.x, self.y, 32.0, 32.0)
+    }
+}
+
+pub struct Enemy {
+    pub kind: EnemyKind,
+    pub x: f64,
+    pub y: f64,
+    pub cooldown: f64,
+    pub health: u32,
+    pub direction: f64,
+    pub vertical_speed: f64,
+    pub state: EnemyState,
+    // Where a flyer was spawned, it bobs around that height
+    pub home_y: f64,
+    // Time since the enemy was spawned
+    pub age: f64,
+    pub burst: Burst,
+    // Where it was before the current tick, drawing blends from there
+    pub last_x: f64,
+    pub last_y: f64
+}
+
+impl Enemy {
+    pub fn new(kind: EnemyKind, x: f64, y: f64, cooldown: f64, health: u32) -> Self {
+        Enemy { kind, x, y, cooldown, health, direction: -1.0, vertical_speed: 0.0, state: EnemyState::Idle, home_y: y, age: 0.0, burst: Burst::default(), last_x: x, last_y: y }
+    }
+
+    // Whether the player is close enough to be noticed, a sight of 0 sees the whole level
+    pub fn sees(&self, player: &Rectangle, sight: f64) -> bool {
+        let hitbox = self.hitbox();
+        let dx = (player.x + player.width / 2.0) - (hitbox.x + hitbox.width / 2.0);
+        let dy = (player.y + player.height / 2.0) - (hitbox.y + hitbox.height / 2.0);
+        sight <= 0.0 || dx.hypot(dy) <= sight
+    }
+
+    // A turret is drawn turned around its position, so its image lies up and left of it
+    pub fn hitbox(&self) -> Rectangle {
+        match self.kind {
+            EnemyKind::Turret => Rectangle::new(self.x - 64.0, self.y - 64.0, 64.0, 64.0),
+            _ => Rectangle::new(self.x, self.y, 64.0, 64.0),
+        }
+    }
+
+    // Where its bullets come out from
+    pub fn muzzle(&self) -> (f64, f64) {
+        match self.kind {
+            EnemyKind::Turret => (self.x - 64.0, self.y + 4.0),
+            _ => (self.x + 16.0, self.y + 16.0),
+        }
+    }
+}
+
+// The shots of an attack that are still to be fired, one every `burst_interval` seconds
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Burst {
+    pub shots: u32,
+    pub timer: f64
+}
+
+impl Burst {
+    pub fn start(&mut self, shots: u32) {
+        self.shots = shots;
+        self.timer = 0.0;
+    }
+
+    // Whether a shot is due once `dt` seconds have passed
+    pub fn fire(&mut self, dt: f64, interval: f64) -> bool {
+        if self.shots == 0 {
+            return false;
+        }
+        self.timer -= dt;
+        if self.timer > 0.0 {
+            return false;
+        }
+        self.shots -= 1;
+        self.timer = interval;
+        true
+    }
+}
+
+pub struct Enemy_Bullet {
+    pub x: f64,
+    pub y: f64,
+    // In pixels per second
+    pub horizontal_speed: f64,
+    pub vertical_speed: f64,
+    // How fast it turns towards the player in radians per second, 0 flies straight
+    pub turn_rate: f64,
+    pub gravity: f64,
+    pub image: Sprite,
+    pub lifetime: f64,
+    // Where it was before the current tick, drawing blends from there
+    pub last_x: f64,
+    pub last_y: f64
+}
+
+impl Enemy_Bullet {
+    pub fn hitbox(&self) -> Rectangle {
+        Rectangle::new(self.x, self.y, 32.0, 32.0)
+    }
+
+    // Move for `dt` seconds, turning towards `(target_x, target_y)` if it homes in and falling if it is lobbed
+    pub fn advance(&mut self, target_x: f64, target_y: f64, dt: f64) {
+        if self.turn_rate > 0.0 {
+            let speed = self.horizontal_speed.hypot(self.vertical_speed);
+            let heading = self.vertical_speed.atan2(self.horizontal_speed);
+            let wanted = (target_y - self.y - 16.0).atan2(target_x - self.x - 16.0);
+            let turn = ((wanted - heading + PI).rem_euclid(2.0 * PI) - PI).clamp(-self.turn_rate * dt, self.turn_rate * dt);
+            self.horizontal_speed = (heading + turn).cos() * speed;
+            self.vertical_speed = (heading + turn).sin() * speed;
+        }
+        self.vertical_speed += self.gravity * dt;
+        self.x += self.horizontal_speed * dt;
+        self.y += self.vertical_speed * dt;
+        self.lifetime -= dt;
+    }
+}
+
+//THE SIZE OF A BOSS, A DEVIL DRAWN TWICE AS LARGE
+pub const BOSS_SIZE: f64 = 128.0;
+
+// A boss guarding an arena, its attacks change with every share of its health taken
+pub struct Boss {
+    pub x: f64,
+    pub y: f64,
+    pub health: u32,
+    pub max_health: u32,
+    pub phases: u32,
+    // The phase the fight has reached so far
+    pub phase: u32,
+    // Seconds until the next attack
+    pub cooldown: f64,
+    pub direction: f64,
+    // Time since the fight started
+    pub age: f64,
+    pub burst: Burst,
+    // Where it was before the current tick, drawing blends from there
+    pub last_x: f64,
+    pub last_y: f64
+}
+
+impl Boss {
+    pub fn new(x: f64, y: f64, health: u32, phases: u32, cooldown: f64) -> Self {
+        Boss { x, y, health, max_health: health, phases, phase: 0, cooldown, direction: -1.0, age: 0.0, burst: Burst::default(), last_x: x, last_y: y }
+    }
+
+    // The phase the remaining health calls for, from 0 up to `phases - 1`
+    pub fn phase_for_health(&self) -> u32 {
+        ((self.max_health - self.health) * self.phases / self.max_health).min(self.phases - 1)
+    }
+
+    pub fn hitbox(&self) -> Rectangle {
+        Rectangle::new(self.x, self.y, BOSS_SIZE, BOSS_SIZE)
+    }
+}
@@ -0,0 +1,437 @@
+use rand::{rngs::StdRng, Rng};
+use crate::config::{GameConfig, PlayerConfig};
+use crate::entities::{EnemyKind, Material};
+
+//THE WIDTH OF A COLUMN OF TERRAIN, ONE BLOCK
+pub const COLUMN_WIDTH: f64 = 128.0;
+//THE HEIGHTS THE GROUND IS USUALLY PLACED AT, FROM THE LOWEST
+const GROUND_HEIGHTS: [f64; 3] = [320.0 + 128.0 / 2.0, 320.0 + 128.0 / 3.0, 320.0];
+//THE HIGHEST A STAIRCASE MAY CLIMB
+const HIGHEST_GROUND: f64 = 128.0;
+//ROOM LEFT BETWEEN THE GROUND AND AN OVERHANG ABOVE IT, ENOUGH FOR A FULL JUMP UNDERNEATH
+const OVERHANG_CLEARANCE: f64 = 160.0;
+//PITS, THE SHARE OF A RUNNING JUMP A PIT MAY TAKE UP AND THE WIDEST ONE IN BLOCKS
+const PIT_SAFETY_MARGIN: f64 = 0.75;
+const MAX_PIT_WIDTH: usize = 3;
+//STEPS, THE SHARE OF THE HIGHEST JUMP A STEP UP MAY TAKE UP
+const STEP_SAFETY_MARGIN: f64 = 0.8;
+//AN ARENA SPANS THE WHOLE SCREEN
+pub const ARENA_COLUMNS: usize = 5;
+//HOW MANY TIMES A CHUNK IS REBUILT BEFORE FALLING BACK TO FLAT GROUND
+const MAX_ATTEMPTS: usize = 8;
+
+//THE SHAPES OF TERRAIN THE GENERATOR STITCHES TOGETHER
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChunkKind {
+    Flat,
+    Staircase,
+    Pits,
+    Overhangs,
+    EnemyNest,
+    PowerupRoom,
+    // Laid down by the world for a boss fight, never picked at random
+    Arena
+}
+
+impl ChunkKind {
+    const ALL: [ChunkKind; 6] = [ChunkKind::Flat, ChunkKind::Staircase, ChunkKind::Pits, ChunkKind::Overhangs, ChunkKind::EnemyNest, ChunkKind::PowerupRoom];
+
+    // How likely the chunk is picked at the start of a run, and once the difficulty has peaked
+    fn weights(&self) -> (f64, f64) {
+        match self {
+            ChunkKind::Flat => (4.0, 1.0),
+            ChunkKind::Staircase => (2.0, 2.0),
+            ChunkKind::Pits => (1.0, 3.0),
+            ChunkKind::Overhangs => (1.0, 2.0),
+            ChunkKind::EnemyNest => (0.0, 2.0),
+            ChunkKind::PowerupRoom => (1.0, 1.0),
+            ChunkKind::Arena => (0.0, 0.0),
+        }
+    }
+}
+
+//ONE BLOCK WIDE SLICE OF TERRAIN
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Column {
+    // The top of the ground and what it is made of, none for a pit
+    pub ground: Option<(f64, Material)>,
+    // The top of a block hanging above the ground
+    pub ceiling: Option<f64>
+}
+
+//A STRETCH OF TERRAIN WITH ITS SPAWNS, POSITIONS ARE A COLUMN INDEX AND A HEIGHT
+pub struct Chunk {
+    pub kind: ChunkKind,
+    pub columns: Vec<Column>,
+    pub enemies: Vec<(usize, f64, EnemyKind)>,
+    pub powerups: Vec<(usize, f64)>
+}
+
+impl Chunk {
+    fn new(kind: ChunkKind) -> Self {
+        Chunk { kind, columns: vec![], enemies: vec![], powerups: vec![] }
+    }
+
+    fn ground(&mut self, y: f64, material: Material) {
+        self.columns.push(Column { ground: Some((y, material)), ceiling: None });
+    }
+
+    fn pit(&mut self, width: usize) {
+        self.columns.extend((0..width).map(|_| Column::default()));
+    }
+
+    // The height the next chunk starts from
+    pub fn exit_y(&self) -> Option<f64> {
+        self.columns.iter().rev().find_map(|column| column.ground).map(|(y, _)| y)
+    }
+
+    // Whether a player can make it from `entry_y` to the end of the chunk with the current tuning
+    fn is_traversable(&self, config: &GameConfig, entry_y: f64) -> bool {
+        let highest_step = jump_height(&config.player) * STEP_SAFETY_MARGIN;
+        let (mut last_y, mut last_material) = (entry_y, Material::Normal);
+        let mut gap = 0;
+        for column in self.columns.iter() {
+            let Some((y, material)) = column.ground else {
+                gap += 1;
+                continue;
+            };
+            let rise = last_y - y;
+            if rise > highest_step || gap > widest_pit(config, last_material, rise) {
+                return false;
+            }
+            if column.ceiling.is_some_and(|ceiling| y - (ceiling + COLUMN_WIDTH) < OVERHANG_CLEARANCE) {
+                return false;
+            }
+            (last_y, last_material) = (y, material);
+            gap = 0;
+        }
+        // A chunk has to end on solid ground, so the next one has somewhere to be jumped to from
+        gap == 0
+    }
+}
+
+// How hard the run has become, from 0 at the start to 1 once `generator.ramp_distance` is covered
+pub fn difficulty(config: &GameConfig, distance: f64) -> f64 {
+    (distance / config.generator.ramp_distance).clamp(0.0, 1.0)
+}
+
+// The top of the highest jump, above the ground it started from
+fn jump_height(player: &PlayerConfig) -> f64 {
+    player.jump_speed * player.jump_speed / (2.0 * player.gravity)
+}
+
+// The widest pit, in blocks, that a running jump is sure to clear when it lands `rise` pixels higher than it took off.
+// The player is assumed to start the run-up from a standstill one block before the pit, on `takeoff`, and to keep
+// speeding up in the air with what `air_control` allows
+fn widest_pit(config: &GameConfig, takeoff: Material, rise: f64) -> usize {
+    let player = &config.player;
+    if jump_height(player) <= rise {
+        return 0;
+    }
+    let dt = 1.0 / 120.0;
+    let running_speed = player.walk_speed * player.running_rate;
+
+    // Run up across the block, as fast as the surface allows
+    let (mut run_up, mut horizontal_speed) = (0.0, 0.0);
+    let (grip, top_speed) = (config.surface.grip(takeoff), config.surface.speed(takeoff));
+    while run_up < COLUMN_WIDTH {
+        horizontal_speed = (horizontal_speed + player.acceleration * grip * dt).min(running_speed * top_speed);
+        run_up += horizontal_speed * dt;
+    }
+
+    // Follow the jump until it comes back down to the landing height
+    let (mut height, mut vertical_speed, mut reach) = (0.0, player.jump_speed, 0.0);
+    while vertical_speed > 0.0 || height > rise {
+        horizontal_speed = (horizontal_speed + player.acceleration * player.air_control * dt).min(running_speed);
+        vertical_speed = (vertical_speed - player.gravity * dt).max(-player.max_fall_speed);
+        height += vertical_speed * dt;
+        reach += horizontal_speed * dt;
+    }
+
+    ((reach * PIT_SAFETY_MARGIN / COLUMN_WIDTH) as usize).min(MAX_PIT_WIDTH)
+}
+
+// The next chunk of terrain, starting from ground at `entry_y`
+pub fn next_chunk(rng: &mut StdRng, config: &GameConfig, entry_y: f64, difficulty: f64) -> Chunk {
+    for _ in 0..MAX_ATTEMPTS {
+        let kind = pick_kind(rng, difficulty);
+        let chunk = build(rng, config, kind, entry_y, difficulty);
+        if chunk.is_traversable(config, entry_y) {
+            return chunk;
+        }
+    }
+    let mut chunk = Chunk::new(ChunkKind::Flat);
+    for _ in 0..4 {
+        chunk.ground(entry_y, Material::Normal);
+    }
+    chunk
+}
+
+// The arena of a boss fight, starting from ground at `entry_y`
+pub fn arena(rng: &mut StdRng, config: &GameConfig, entry_y: f64) -> Chunk {
+    build(rng, config, ChunkKind::Arena, entry_y, 0.0)
+}
+
+fn pick_kind(rng: &mut StdRng, difficulty: f64) -> ChunkKind {
+    let weight = |kind: &ChunkKind| {
+        let (easy, hard) = kind.weights();
+        easy + (hard - easy) * difficulty
+    };
+    let mut roll = rng.gen::<f64>() * ChunkKind::ALL.iter().map(weight).sum::<f64>();
+    for kind in ChunkKind::ALL {
+        roll -= weight(&kind);
+        if roll < 0.0 {
+            return kind;
+        }
+    }
+    ChunkKind::Flat
+}
+
+// How likely an enemy of a nest is of each kind at the start of a run, and once the difficulty has peaked
+fn enemy_weights(kind: EnemyKind) -> (f64, f64) {
+    match kind {
+        EnemyKind::Turret => (3.0, 1.0),
+        EnemyKind::Walker => (2.0, 2.0),
+        EnemyKind::Flyer => (0.0, 2.0),
+        EnemyKind::Charger => (0.0, 2.0),
+        EnemyKind::Devil => (0.0, 1.5),
+    }
+}
+
+fn pick_enemy(rng: &mut StdRng, difficulty: f64) -> EnemyKind {
+    let weight = |kind: &EnemyKind| {
+        let (easy, hard) = enemy_weights(*kind);
+        easy + (hard - easy) * difficulty
+    };
+    let mut roll = rng.gen::<f64>() * EnemyKind::ALL.iter().map(weight).sum::<f64>();
+    for kind in EnemyKind::ALL {
+        roll -= weight(&kind);
+        if roll < 0.0 {
+            return kind;
+        }
+    }
+    EnemyKind::Turret
+}
+
+fn random_height(rng: &mut StdRng) -> f64 {
+    GROUND_HEIGHTS[rng.gen_range(0..GROUND_HEIGHTS.len())]
+}
+
+fn random_material(rng: &mut StdRng, config: &GameConfig) -> Material {
+    let roll: f64 = rng.gen();
+    if roll < config.spawn.ice_chance {
+        Material::Ice
+    } else if roll < config.spawn.ice_chance + config.spawn.sticky_chance {
+        Material::Sticky
+    } else {
+        Material::Normal
+    }
+}
+
+fn build(rng: &mut StdRng, config: &GameConfig, kind: ChunkKind, entry_y: f64, difficulty: f64) -> Chunk {
+    let mut chunk = Chunk::new(kind);
+    match kind {
+        //UNEVEN GROUND, LIKE THE ORIGINAL TERRAIN
+        ChunkKind::Flat => {
+            let mut y = entry_y;
+            for _ in 0..rng.gen_range(3..=6) {
+                if rng.gen_bool(0.5) {
+                    y = random_height(rng);
+                }
+                chunk.ground(y, random_material(rng, config));
+            }
+        },
+        //STEPS GOING UP FROM LOW GROUND, OR DOWN FROM HIGH GROUND
+        ChunkKind::Staircase => {
+            let step = if rng.gen_bool(difficulty * 0.5 + 0.25) { 64.0 } else { 128.0 / 3.0 };
+            let up = entry_y >= GROUND_HEIGHTS[2];
+            let mut y = entry_y;
+            for _ in 0..rng.gen_range(3..=5) {
+                y = if up { (y - step).max(HIGHEST_GROUND) } else { (y + step).min(GROUND_HEIGHTS[0]) };
+                chunk.ground(y, Material::Normal);
+                if difficulty < 0.5 {
+                    chunk.ground(y, Material::Normal);
+                }
+            }
+        },
+        //PITS, WIDER AS THE RUN GOES ON BUT NEVER WIDER THAN A RUNNING JUMP
+        ChunkKind::Pits => {
+            let mut y = entry_y;
+            chunk.ground(y, Material::Normal);
+            for _ in 0..rng.gen_range(1..=1 + (difficulty * 2.0).round() as usize) {
+                let landing_y = random_height(rng);
+                let takeoff = chunk.columns.last().and_then(|column| column.ground).map_or(Material::Normal, |(_, material)| material);
+                let widest = widest_pit(config, takeoff, y - landing_y);
+                let width = ((widest as f64 * (0.4 + 0.6 * difficulty)).ceil() as usize).clamp(1, widest.max(1));
+                chunk.pit(width);
+                y = landing_y;
+                for _ in 0..rng.gen_range(1..=2) {
+                    chunk.ground(y, random_material(rng, config));
+                }
+            }
+        },
+        //A TUNNEL OF BLOCKS HANGING ABOVE FLAT GROUND
+        ChunkKind::Overhangs => {
+            let y = random_height(rng);
+            let width = rng.gen_range(4..=6);
+            let start = rng.gen_range(1..width - 1);
+            for i in 0..width {
+                chunk.ground(y, Material::Normal);
+                if i >= start {
+                    chunk.columns[i].ceiling = Some(y - OVERHANG_CLEARANCE - COLUMN_WIDTH);
+                }
+            }
+        },
+        //FLAT GROUND WATCHED OVER BY A GROUP OF ENEMIES, MORE AND TOUGHER ONES LATER IN THE RUN
+        ChunkKind::EnemyNest => {
+            let y = random_height(rng);
+            let width = 4;
+            for _ in 0..width {
+                chunk.ground(y, Material::Normal);
+            }
+            for _ in 0..1 + (difficulty * 3.0).round() as usize {
+                let kind = pick_enemy(rng, difficulty);
+                // Walking enemies stand on the ground, the others keep to the air
+                let enemy_y = if kind.walks() { y - 64.0 } else { rng.gen_range(64..160) as f64 };
+                chunk.enemies.push((rng.gen_range(0..width), enemy_y, kind));
+            }
+        },
+        //A CLOCK SHELTERED UNDER A ROOF
+        ChunkKind::PowerupRoom => {
+            let y = random_height(rng);
+            for i in 0..5 {
+                chunk.ground(y, Material::Normal);
+                if (1..4).contains(&i) {
+                    chunk.columns[i].ceiling = Some(y - OVERHANG_CLEARANCE - COLUMN_WIDTH);
+                }
+            }
+            chunk.powerups.push((2, y - 56.0));
+        },
+        //LEVEL GROUND WITH NOTHING ON IT, FOR A BOSS TO BE FOUGHT ON
+        ChunkKind::Arena => {
+            for _ in 0..ARENA_COLUMNS {
+                chunk.ground(entry_y, Material::Normal);
+            }
+        },
+    }
+    chunk
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use super::*;
+
+    fn chunk(columns: &[Option<f64>]) -> Chunk {
+        let mut chunk = Chunk::new(ChunkKind::Pits);
+        chunk.columns = columns.iter().map(|y| Column { ground: y.map(|y| (y, Material::Normal)), ceiling: None }).collect();
+        chunk
+    }
+
+    #[test]
+    fn level_pit_fits_a_running_jump() {
+        let config = GameConfig::default();
+        let widest = widest_pit(&config, Material::Normal, 0.0);
+        assert!(widest >= 1);
+        assert!(chunk(&[Some(320.0), None, Some(320.0)]).is_traversable(&config, 320.0));
+        let mut columns = vec![Some(320.0)];
+        columns.extend((0..=widest).map(|_| None));
+        columns.push(Some(320.0));
+        assert!(!chunk(&columns).is_traversable(&config, 320.0));
+    }
+
+    #[test]
+    fn pit_is_narrower_for_a_slow_player() {
+        let mut config = GameConfig::default();
+        config.player.walk_speed = 40.0;
+        assert_eq!(widest_pit(&config, Material::Normal, 0.0), 0);
+        assert!(!chunk(&[Some(320.0), None, Some(320.0)]).is_traversable(&config, 320.0));
+    }
+
+    #[test]
+    fn slow_run_up_narrows_the_pit() {
+        let mut config = GameConfig::default();
+        config.player.air_control = 0.0;
+        config.surface.ice_grip = 0.01;
+        assert!(widest_pit(&config, Material::Ice, 0.0) < widest_pit(&config, Material::Normal, 0.0));
+    }
+
+    #[test]
+    fn rise_above_the_jump_is_rejected() {
+        let config = GameConfig::default();
+        let height = jump_height(&config.player);
+        assert_eq!(widest_pit(&config, Material::Normal, height + 1.0), 0);
+        assert!(!chunk(&[Some(320.0), Some(320.0 - height - 1.0)]).is_traversable(&config, 320.0));
+        assert!(chunk(&[Some(320.0), Some(320.0 - height * STEP_SAFETY_MARGIN + 1.0)]).is_traversable(&config, 320.0));
+    }
+
+    #[test]
+    fn chunk_ending_in_a_pit_is_rejected() {
+        assert!(!chunk(&[Some(320.0), None]).is_traversable(&GameConfig::default(), 320.0));
+    }
+
+    fn pits(chunk: &Chunk) -> Vec<usize> {
+        chunk.columns.split(|column| column.ground.is_some()).map(|pit| pit.len()).filter(|&width| width > 0).collect()
+    }
+
+    #[test]
+    fn difficulty_shifts_the_chunk_mix() {
+        let count = |difficulty: f64, kind: ChunkKind| {
+            let mut rng = StdRng::seed_from_u64(7);
+            (0..2000).filter(|_| pick_kind(&mut rng, difficulty) == kind).count()
+        };
+        assert_eq!(count(0.0, ChunkKind::EnemyNest), 0);
+        assert!(count(1.0, ChunkKind::EnemyNest) > 0);
+        assert!(count(0.0, ChunkKind::Flat) > 2 * count(1.0, ChunkKind::Flat));
+        assert!(count(0.0, ChunkKind::Pits) < count(1.0, ChunkKind::Pits));
+        assert_eq!(count(0.5, ChunkKind::Arena), 0);
+    }
+
+    #[test]
+    fn difficulty_widens_the_pits() {
+        let config = GameConfig::default();
+        let widths = |difficulty: f64| {
+            let mut rng = StdRng::seed_from_u64(7);
+            let widths: Vec<usize> = (0..200).flat_map(|_| pits(&build(&mut rng, &config, ChunkKind::Pits, GROUND_HEIGHTS[1], difficulty))).collect();
+            (widths.iter().sum::<usize>() as f64 / widths.len() as f64, widths.len() as f64 / 200.0)
+        };
+        let (easy_width, easy_count) = widths(0.0);
+        let (hard_width, hard_count) = widths(1.0);
+        assert!(easy_width < hard_width);
+        assert!(easy_count < hard_count);
+        assert!(hard_width <= MAX_PIT_WIDTH as f64);
+    }
+
+    #[test]
+    fn falls_back_to_flat_ground_only_once_every_attempt_failed() {
+        // A player who can barely jump, far below every ground height, so most chunks cannot be climbed out of
+        let mut config = GameConfig::default();
+        config.player.jump_speed = 1.0;
+        let entry_y = 1000.0;
+        let mut fallbacks = 0;
+        for seed in 0..100 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut replay = rng.clone();
+            let chunk = next_chunk(&mut rng, &config, entry_y, 0.5);
+            let expected = (0..MAX_ATTEMPTS)
+                .map(|_| {
+                    let kind = pick_kind(&mut replay, 0.5);
+                    build(&mut replay, &config, kind, entry_y, 0.5)
+                })
+                .find(|chunk| chunk.is_traversable(&config, entry_y));
+            match expected {
+                Some(expected) => {
+                    assert_eq!(chunk.kind, expected.kind);
+                    assert_eq!(format!("{:?}", chunk.columns), format!("{:?}", expected.columns));
+                },
+                None => {
+                    fallbacks += 1;
+                    assert_eq!(chunk.kind, ChunkKind::Flat);
+                    assert!(chunk.columns.iter().all(|column| column.ground == Some((entry_y, Material::Normal))));
+                    assert_eq!(rng.gen::<u64>(), replay.gen::<u64>());
+                },
+            }
+        }
+        assert!(fallbacks > 0 && fallbacks < 100);
+    }
+}
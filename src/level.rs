@@ -0,0 +1,222 @@
+use std::{error::Error, fmt, fs, io, path::{Path, PathBuf}};
+use serde::{Deserialize, Serialize};
+use crate::entities::{EnemyKind, Material, Sprite};
+use crate::generator::COLUMN_WIDTH;
+use crate::tiled;
+
+// A hand-authored stretch of the world, read from a file in `resources/levels/`, either TOML or a Tiled map
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Level {
+    #[serde(default)]
+    pub name: String,
+    // Where the player starts, only used for the first level of the run
+    pub spawn: Option<Point>,
+    // Where the wall starts, only used for the first level of the run
+    pub wall_start: Option<f64>,
+    // The top of the ground the endless terrain continues from, the highest block of the last column by default
+    pub exit_y: Option<f64>,
+    pub blocks: Vec<LevelBlock>,
+    #[serde(default)]
+    pub enemies: Vec<LevelEnemy>,
+    #[serde(default)]
+    pub powerups: Vec<Point>
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64
+}
+
+// An enemy, a turret unless `kind` says otherwise
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LevelEnemy {
+    pub x: f64,
+    pub y: f64,
+    #[serde(default)]
+    pub kind: EnemyKind
+}
+
+// A 128px block, placed by its top left corner
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LevelBlock {
+    pub x: f64,
+    pub y: f64,
+    #[serde(default)]
+    pub material: Material,
+    #[serde(default = "LevelBlock::default_image")]
+    pub image: Sprite
+}
+
+impl LevelBlock {
+    fn default_image() -> Sprite {
+        Sprite::Block
+    }
+}
+
+//WHY A LEVEL FILE COULD NOT BE USED
+#[derive(Debug)]
+pub enum LevelError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    // A Tiled map that could not be read or uses something the game has no equivalent for
+    Tiled(PathBuf, String),
+    Invalid(PathBuf, &'static str)
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(path, error) => write!(f, "could not read {}: {}", path.display(), error),
+            LevelError::Parse(path, error) => write!(f, "could not parse {}: {}", path.display(), error),
+            LevelError::Tiled(path, reason) => write!(f, "could not import {}: {}", path.display(), reason),
+            LevelError::Invalid(path, reason) => write!(f, "invalid level {}: {}", path.display(), reason),
+        }
+    }
+}
+
+impl Error for LevelError {}
+
+impl Level {
+    pub fn load(path: &Path) -> Result<Level, LevelError> {
+        let text = fs::read_to_string(path).map_err(|error| LevelError::Io(path.to_path_buf(), error))?;
+        let level = match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension @ ("tmj" | "tmx")) => tiled::import(&text, extension).map_err(|reason| LevelError::Tiled(path.to_path_buf(), reason))?,
+            _ => toml::from_str(&text).map_err(|error| LevelError::Parse(path.to_path_buf(), error))?,
+        };
+        level.validate().map_err(|reason| LevelError::Invalid(path.to_path_buf(), reason))?;
+        Ok(level)
+    }
+
+    // Every level of `dir` in file name order, the run plays them one after another
+    pub fn load_all(dir: &Path) -> Result<Vec<Level>, LevelError> {
+        Level::files(dir)?.iter().map(|path| Level::load(path)).collect()
+    }
+
+    // The level files of `dir` (`.toml`, or `.tmj` and `.tmx` from Tiled) in file name order
+    pub fn files(dir: &Path) -> Result<Vec<PathBuf>, LevelError> {
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let entries = fs::read_dir(dir).map_err(|error| LevelError::Io(dir.to_path_buf(), error))?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| ["toml", "tmj", "tmx"].iter().any(|known| extension == *known)))
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    // Write the level as TOML, laid out like the hand-written files with one block per line
+    pub fn save(&self, path: &Path) -> Result<(), LevelError> {
+        self.validate().map_err(|reason| LevelError::Invalid(path.to_path_buf(), reason))?;
+        fs::write(path, self.to_toml()).map_err(|error| LevelError::Io(path.to_path_buf(), error))
+    }
+
+    fn to_toml(&self) -> String {
+        let point = |point: &Point| format!("{{ x = {}, y = {} }}", point.x, point.y);
+        let list = |name: &str, items: Vec<String>| {
+            let lines: String = items.iter().map(|item| format!("    {},\n", item)).collect();
+            format!("\n{} = [\n{}]\n", name, lines)
+        };
+
+        let mut text = format!("name = {}\n", toml_value(&self.name));
+        if let Some(spawn) = &self.spawn {
+            text += &format!("spawn = {}\n", point(spawn));
+        }
+        if let Some(wall_start) = self.wall_start {
+            text += &format!("wall_start = {}\n", wall_start);
+        }
+        if let Some(exit_y) = self.exit_y {
+            text += &format!("exit_y = {}\n", exit_y);
+        }
+        text += &list("blocks", self.blocks.iter().map(|block| {
+            let mut fields = format!("x = {}, y = {}", block.x, block.y);
+            if block.material != Material::Normal {
+                fields += &format!(", material = {}", toml_value(&block.material));
+            }
+            if block.image != Sprite::Block {
+                fields += &format!(", image = {}", toml_value(&block.image));
+            }
+            format!("{{ {} }}", fields)
+        }).collect());
+        if !self.enemies.is_empty() {
+            text += &list("enemies", self.enemies.iter().map(|enemy| match enemy.kind {
+                EnemyKind::Turret => format!("{{ x = {}, y = {} }}", enemy.x, enemy.y),
+                kind => format!("{{ x = {}, y = {}, kind = {} }}", enemy.x, enemy.y, toml_value(&kind)),
+            }).collect());
+        }
+        if !self.powerups.is_empty() {
+            text += &list("powerups", self.powerups.iter().map(point).collect());
+        }
+        text
+    }
+
+    // Whether the level can be played, the reason it cannot otherwise
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let checks = [
+            (!self.blocks.is_empty(), "a level needs at least one block"),
+            (self.blocks.iter().all(|block| block.x >= 0.0), "blocks must not be placed left of x = 0"),
+            (self.enemies.iter().all(|enemy| enemy.x >= 0.0) && self.powerups.iter().all(|point| point.x >= 0.0), "spawns must not be placed left of x = 0"),
+        ];
+        match checks.iter().find(|(ok, _)| !ok) {
+            Some((_, reason)) => Err(reason),
+            None => Ok(()),
+        }
+    }
+
+    // How far the level reaches, rounded up to whole columns so the endless terrain lines up after it
+    pub fn width(&self) -> f64 {
+        let right = self.blocks.iter().map(|block| block.x + COLUMN_WIDTH).fold(0.0, f64::max);
+        (right / COLUMN_WIDTH).ceil() * COLUMN_WIDTH
+    }
+
+    // The top of the ground at the end of the level, none for a level without blocks such as one still being drawn in the editor
+    pub fn exit_y(&self) -> Option<f64> {
+        let last_column = self.width() - COLUMN_WIDTH;
+        self.exit_y.or_else(|| {
+            self.blocks.iter()
+                .filter(|block| block.x + COLUMN_WIDTH > last_column)
+                .map(|block| block.y)
+                .min_by(f64::total_cmp)
+        })
+    }
+}
+
+// A string or an enum written the way TOML expects it, quoted and escaped
+fn toml_value<T: Serialize>(value: &T) -> String {
+    toml::Value::try_from(value).map(|value| value.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(text: &str) -> Level {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn exit_is_the_highest_block_of_the_last_column() {
+        let level = level("blocks = [{ x = 0, y = 320 }, { x = 128, y = 320 }, { x = 128, y = 192 }]");
+        assert_eq!(level.width(), 256.0);
+        assert_eq!(level.exit_y(), Some(192.0));
+    }
+
+    #[test]
+    fn exit_y_given_in_the_file_wins() {
+        let level = level("exit_y = 256\nblocks = [{ x = 0, y = 320 }]");
+        assert_eq!(level.exit_y(), Some(256.0));
+    }
+
+    #[test]
+    fn level_without_blocks_has_no_exit_and_does_not_validate() {
+        let level = level("blocks = []");
+        assert_eq!(level.exit_y(), None);
+        assert!(level.validate().is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use ggez::{glam, graphics::{self, Canvas, Color}, input::keyboard::{KeyCode, KeyInput}, Context, GameResult};
+use crate::entities::Sprite;
+use super::{draw_centered, Scene, Shared, Transition};
+
+//SECONDS EACH PANEL STAYS ON SCREEN BEFORE MOVING ON BY ITSELF
+const PANEL_DURATION: f64 = 3.0;
+//THE PANELS ARE 64x48 PIXEL ART, SCALED UP TO FILL THE WINDOW
+const PANEL_SCALE: f32 = 10.0;
+
+// A sequence of full screen panels, followed by the scene it leads into
+pub struct Cutscene {
+    panels: Vec<Sprite>,
+    current: usize,
+    elapsed: f64,
+    next: Option<Box<dyn Scene>>
+}
+
+impl Cutscene {
+    pub fn new(panels: Vec<Sprite>, next: Box<dyn Scene>) -> Self {
+        Cutscene { panels, current: 0, elapsed: 0.0, next: Some(next) }
+    }
+
+    // The story shown before the first run
+    pub fn intro(next: Box<dyn Scene>) -> Self {
+        Self::new(vec![Sprite::Cutscene1, Sprite::Cutscene2, Sprite::Cutscene3, Sprite::Cutscene4, Sprite::Cutscene5], next)
+    }
+
+    fn advance(&mut self) -> Transition {
+        self.current += 1;
+        self.elapsed = 0.0;
+        if self.current < self.panels.len() {
+            Transition::None
+        } else {
+            self.skip()
+        }
+    }
+
+    fn skip(&mut self) -> Transition {
+        match self.next.take() {
+            Some(next) => Transition::Replace(next),
+            None => Transition::Pop,
+        }
+    }
+}
+
+impl Scene for Cutscene {
+    fn update(&mut self, ctx: &mut Context, _shared: &mut Shared) -> GameResult<Transition> {
+        self.elapsed += ctx.time.delta().as_secs_f64();
+        if self.elapsed >= PANEL_DURATION {
+            return Ok(self.advance());
+        }
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut Canvas, shared: &Shared) -> GameResult {
+        if let Some(panel) = self.panels.get(self.current) {
+            canvas.set_sampler(graphics::Sampler::nearest_clamp());
+            canvas.draw(shared.assets.image(*panel), graphics::DrawParam::new().dest(glam::Vec2::new(0.0, 0.0)).scale(glam::Vec2::splat(PANEL_SCALE)));
+            canvas.set_sampler(graphics::Sampler::default());
+        }
+        draw_centered(canvas, "SPACE: NEXT    ESC: SKIP", 460.0, 14.0, Color::WHITE);
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, _shared: &mut Shared) -> GameResult<Transition> {
+        Ok(match input.keycode {
+            Some(KeyCode::Space) | Some(KeyCode::Return) => self.advance(),
+            Some(KeyCode::Escape) => self.skip(),
+            _ => Transition::None,
+        })
+    }
+}
@@ -0,0 +1,328 @@
+use ggez::{glam::{self, Vec2}, graphics::{self, Canvas, Color}, input::{keyboard::{KeyCode, KeyInput}, mouse::MouseButton}, Context, GameResult};
+use std::path::PathBuf;
+use crate::camera::{Camera, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::entities::{EnemyKind, Material, Sprite};
+use crate::generator::COLUMN_WIDTH;
+use crate::level::{Level, LevelBlock, LevelEnemy, Point};
+use super::{Playing, Scene, Shared, Title, Transition};
+
+//KEYSTROKES
+const SCROLL_LEFT_KEY: KeyCode = KeyCode::Left;
+const SCROLL_RIGHT_KEY: KeyCode = KeyCode::Right;
+const SCROLL_UP_KEY: KeyCode = KeyCode::Up;
+const SCROLL_DOWN_KEY: KeyCode = KeyCode::Down;
+const PLAYTEST_KEY: KeyCode = KeyCode::Return;
+const SAVE_KEY: KeyCode = KeyCode::S;
+const PREVIOUS_FILE_KEY: KeyCode = KeyCode::PageUp;
+const NEXT_FILE_KEY: KeyCode = KeyCode::PageDown;
+const EDITOR_KEY: KeyCode = KeyCode::F2;
+
+//BLOCKS SNAP TO WHOLE COLUMNS, AND TO HALF A BLOCK VERTICALLY SO THE GROUND CAN SIT AT THE USUAL HEIGHTS
+const ROW_HEIGHT: f64 = COLUMN_WIDTH / 2.0;
+//HOW FAR FROM AN ENEMY, A CLOCK OR THE SPAWN A RIGHT CLICK STILL REMOVES IT
+const PICK_RADIUS: f64 = 32.0;
+//HOW FAST THE ARROW KEYS SCROLL THE VIEW, IN PIXELS PER SECOND
+const SCROLL_SPEED: f64 = 600.0;
+//HOW LONG A MESSAGE SUCH AS A SAVED FILE STAYS ON SCREEN, IN SECONDS
+const STATUS_DURATION: f64 = 3.0;
+
+//WHAT A LEFT CLICK PUTS DOWN
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Tool {
+    Block(Material),
+    Enemy(EnemyKind),
+    Powerup,
+    Spawn
+}
+
+impl Tool {
+    fn name(&self) -> String {
+        match self {
+            Tool::Block(Material::Normal) => "BLOCK".to_string(),
+            Tool::Block(Material::Ice) => "ICE BLOCK".to_string(),
+            Tool::Block(Material::Sticky) => "STICKY BLOCK".to_string(),
+            Tool::Enemy(kind) => format!("ENEMY: {}", kind.name().to_uppercase()),
+            Tool::Powerup => "CLOCK".to_string(),
+            Tool::Spawn => "SPAWN".to_string(),
+        }
+    }
+}
+
+// Builds the TOML levels of `resources/levels/` with the mouse, one file at a time
+pub struct Editor {
+    // The TOML files of the level folder, followed by a new file that does not exist yet
+    files: Vec<PathBuf>,
+    file: usize,
+    level: Level,
+    camera: Camera,
+    tool: Tool,
+    unsaved: bool,
+    // Set after a first attempt to leave a level with unsaved changes, the next one goes through
+    discard_armed: bool,
+    status: Option<(String, f64)>
+}
+
+impl Editor {
+    pub fn new(shared: &Shared) -> Self {
+        let mut editor = Editor { files: vec![], file: 0, level: Self::empty_level(), camera: Camera::new(), tool: Tool::Block(Material::Normal), unsaved: false, discard_armed: false, status: None };
+        editor.refresh_files(shared);
+        editor.open(0);
+        editor
+    }
+
+    fn empty_level() -> Level {
+        Level { name: String::new(), spawn: None, wall_start: None, exit_y: None, blocks: vec![], enemies: vec![], powerups: vec![] }
+    }
+
+    // List the level files again, Tiled maps are left out as they are edited in Tiled
+    fn refresh_files(&mut self, shared: &Shared) {
+        let mut files: Vec<PathBuf> = match Level::files(&shared.level_dir) {
+            Ok(files) => files.into_iter().filter(|path| path.extension().is_some_and(|extension| extension == "toml")).collect(),
+            Err(error) => {
+                self.show(error.to_string());
+                vec![]
+            }
+        };
+        // Numbering after the existing files, skipping any name already taken where the numbering has gaps, so saving never overwrites a level
+        let new_file = |number: usize| shared.level_dir.join(format!("{:02}_custom.toml", number));
+        let mut number = files.len() + 1;
+        while new_file(number).exists() {
+            number += 1;
+        }
+        files.push(new_file(number));
+        self.files = files;
+    }
+
+    fn open(&mut self, file: usize) {
+        self.file = file;
+        self.level = Self::empty_level();
+        let path = &self.files[file];
+        if path.exists() {
+            match Level::load(path) {
+                Ok(level) => self.level = level,
+                Err(error) => self.show(error.to_string()),
+            }
+        }
+        self.camera = Camera::new();
+        self.unsaved = false;
+        self.discard_armed = false;
+    }
+
+    fn show(&mut self, text: String) {
+        self.status = Some((text, STATUS_DURATION));
+    }
+
+    fn edited(&mut self) {
+        self.unsaved = true;
+        self.discard_armed = false;
+    }
+
+    // Whether the level may be left, asking once for confirmation when it has unsaved changes
+    fn may_leave(&mut self) -> bool {
+        if !self.unsaved || self.discard_armed {
+            return true;
+        }
+        self.discard_armed = true;
+        self.show("UNSAVED CHANGES, PRESS AGAIN TO DISCARD THEM".to_string());
+        false
+    }
+
+    fn save(&mut self, shared: &mut Shared) {
+        let path = self.files[self.file].clone();
+        if let Err(error) = self.level.save(&path) {
+            self.show(error.to_string());
+            return;
+        }
+        self.unsaved = false;
+        self.discard_armed = false;
+        // The next run plays the level as saved
+        match Level::load_all(&shared.level_dir) {
+            Ok(levels) => shared.levels = levels,
+            Err(error) => eprintln!("{}", error),
+        }
+        self.refresh_files(shared);
+        self.file = self.files.iter().position(|file| *file == path).unwrap_or(self.file);
+        self.show(format!("SAVED {}", path.display()));
+    }
+
+    // The mouse position in the level, and the top left corner of the grid cell under it
+    fn cursor(&self, ctx: &Context) -> (Point, Point) {
+        let position = ctx.mouse.position();
+        let (x, y) = self.camera.to_world(position.x as f64, position.y as f64);
+        let cell = Point { x: (x / COLUMN_WIDTH).floor() * COLUMN_WIDTH, y: (y / ROW_HEIGHT).floor() * ROW_HEIGHT };
+        (Point { x, y }, cell)
+    }
+
+    fn place(&mut self, point: Point, cell: Point, just_pressed: bool) {
+        match self.tool {
+            //BLOCKS ARE PAINTED WHILE THE BUTTON IS HELD
+            Tool::Block(material) => {
+                match self.level.blocks.iter_mut().find(|block| block.x == cell.x && block.y == cell.y) {
+                    Some(block) if block.material == material => return,
+                    Some(block) => block.material = material,
+                    None => self.level.blocks.push(LevelBlock { x: cell.x, y: cell.y, material, image: Sprite::Block }),
+                }
+            },
+            //EVERYTHING ELSE ONCE PER CLICK
+            _ if !just_pressed => return,
+            Tool::Enemy(kind) => self.level.enemies.push(LevelEnemy { x: point.x, y: point.y, kind }),
+            Tool::Powerup => self.level.powerups.push(point),
+            Tool::Spawn => self.level.spawn = Some(point),
+        }
+        self.edited();
+    }
+
+    // Remove what is under the cursor, the spawns first as they sit in front of the blocks
+    fn remove(&mut self, point: Point) {
+        let near = |other: &Point| (other.x - point.x).hypot(other.y - point.y) < PICK_RADIUS;
+        let level = &mut self.level;
+        if let Some(i) = level.enemies.iter().position(|enemy| near(&Point { x: enemy.x, y: enemy.y })) {
+            level.enemies.remove(i);
+        } else if let Some(i) = level.powerups.iter().position(near) {
+            level.powerups.remove(i);
+        } else if level.spawn.as_ref().is_some_and(near) {
+            level.spawn = None;
+        } else if let Some(i) = level.blocks.iter().position(|block| block.x <= point.x && point.x < block.x + COLUMN_WIDTH && block.y <= point.y && point.y < block.y + COLUMN_WIDTH) {
+            level.blocks.remove(i);
+        } else {
+            return;
+        }
+        self.edited();
+    }
+
+    // Play the level being edited with the player dropped at the mouse, the editor comes back when the run ends
+    fn playtest(&self, ctx: &Context, shared: &Shared) -> Result<Playing, &'static str> {
+        let (point, _) = self.cursor(ctx);
+        let mut level = self.level.clone();
+        level.spawn = Some(point);
+        level.wall_start = Some(point.x + shared.config.wall.start_x);
+        level.validate()?;
+        Ok(Playing::playtest(shared.next_seed(), level, shared))
+    }
+
+    fn to_screen(&self, x: f64, y: f64) -> Vec2 {
+        let (x, y) = self.camera.to_screen(x, y);
+        glam::Vec2::new(x as f32, y as f32)
+    }
+
+    fn draw_rect(canvas: &mut Canvas, dst: Vec2, width: f32, height: f32, color: Color) {
+        canvas.draw(&graphics::Quad, graphics::DrawParam::new().dest(dst).scale(glam::Vec2::new(width, height)).color(color));
+    }
+}
+
+impl Scene for Editor {
+    fn update(&mut self, ctx: &mut Context, _shared: &mut Shared) -> GameResult<Transition> {
+        let dt = ctx.time.delta().as_secs_f64();
+        if let Some((_, time_left)) = self.status.as_mut() {
+            *time_left -= dt;
+        }
+        self.status = self.status.take().filter(|(_, time_left)| *time_left > 0.0);
+
+        //SCROLL
+        let k_ctx = &ctx.keyboard;
+        let axis = |negative: KeyCode, positive: KeyCode| k_ctx.is_key_pressed(positive) as i32 as f64 - k_ctx.is_key_pressed(negative) as i32 as f64;
+        let (dx, dy) = (axis(SCROLL_LEFT_KEY, SCROLL_RIGHT_KEY), axis(SCROLL_UP_KEY, SCROLL_DOWN_KEY));
+        self.camera.scroll(dx * SCROLL_SPEED * dt, dy * SCROLL_SPEED * dt);
+
+        //EDIT
+        let (point, cell) = self.cursor(ctx);
+        if ctx.mouse.button_pressed(MouseButton::Left) {
+            self.place(point, cell, ctx.mouse.button_just_pressed(MouseButton::Left));
+        } else if ctx.mouse.button_pressed(MouseButton::Right) {
+            self.remove(point);
+        }
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, shared: &Shared) -> GameResult {
+        let assets = &shared.assets;
+
+        //GRID, ONE LINE PER COLUMN
+        let first_column = (self.camera.x / COLUMN_WIDTH).floor() * COLUMN_WIDTH;
+        let mut x = first_column;
+        while x < self.camera.x + SCREEN_WIDTH {
+            Self::draw_rect(canvas, self.to_screen(x, self.camera.y), 1.0, SCREEN_HEIGHT as f32, Color::new(1.0, 1.0, 1.0, 0.15));
+            x += COLUMN_WIDTH;
+        }
+
+        //THE LEVEL
+        for block in self.level.blocks.iter().filter(|block| self.camera.is_visible(block.x, COLUMN_WIDTH)) {
+            let tint = match block.material {
+                Material::Normal => Color::WHITE,
+                Material::Ice => Color::new(0.6, 0.85, 1.0, 1.0),
+                Material::Sticky => Color::new(0.7, 1.0, 0.5, 1.0),
+            };
+            canvas.draw(assets.image(block.image), graphics::DrawParam::new().dest(self.to_screen(block.x, block.y)).color(tint));
+        }
+        for enemy in self.level.enemies.iter() {
+            let (image, rotation) = enemy.kind.looks(-1.0);
+            canvas.draw(assets.image(image), graphics::DrawParam::new().dest(self.to_screen(enemy.x, enemy.y)).rotation(rotation));
+        }
+        for powerup in self.level.powerups.iter() {
+            canvas.draw(assets.image(Sprite::Powerup), graphics::DrawParam::new().dest(self.to_screen(powerup.x, powerup.y)));
+        }
+        if let Some(spawn) = &self.level.spawn {
+            canvas.draw(assets.image(Sprite::PlayerRight), graphics::DrawParam::new().dest(self.to_screen(spawn.x, spawn.y)));
+        }
+
+        //WHAT A CLICK WOULD PUT DOWN
+        let (point, cell) = self.cursor(ctx);
+        let ghost = Color::new(1.0, 1.0, 1.0, 0.5);
+        match self.tool {
+            Tool::Block(_) => Self::draw_rect(canvas, self.to_screen(cell.x, cell.y), COLUMN_WIDTH as f32, COLUMN_WIDTH as f32, Color::new(1.0, 1.0, 1.0, 0.25)),
+            Tool::Enemy(kind) => {
+                let (image, rotation) = kind.looks(-1.0);
+                canvas.draw(assets.image(image), graphics::DrawParam::new().dest(self.to_screen(point.x, point.y)).rotation(rotation).color(ghost));
+            },
+            Tool::Powerup => canvas.draw(assets.image(Sprite::Powerup), graphics::DrawParam::new().dest(self.to_screen(point.x, point.y)).color(ghost)),
+            Tool::Spawn => canvas.draw(assets.image(Sprite::PlayerRight), graphics::DrawParam::new().dest(self.to_screen(point.x, point.y)).color(ghost)),
+        }
+
+        //WHAT IS BEING EDITED, AND HOW
+        let file_name = self.files[self.file].file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let text = graphics::Text::new(format!(
+            "{}{}\nTOOL: {}    X: {:.0} Y: {:.0}",
+            file_name, if self.unsaved { " *" } else { "" }, self.tool.name(), point.x, point.y
+        ));
+        canvas.draw(&text, graphics::DrawParam::new().dest(glam::Vec2::new(8.0, 8.0)).color(Color::WHITE));
+        let help = graphics::Text::new(
+            "LEFT CLICK: PLACE    RIGHT CLICK: REMOVE    ARROWS: SCROLL\n1-3: BLOCKS  4: ENEMIES  5: CLOCK  6: SPAWN    ENTER: PLAYTEST\nS: SAVE    PAGE UP/DOWN: OTHER LEVEL    F2/ESC: TITLE"
+        );
+        canvas.draw(&help, graphics::DrawParam::new().dest(glam::Vec2::new(8.0, SCREEN_HEIGHT as f32 - 56.0)).color(Color::WHITE));
+        if let Some((text, _)) = &self.status {
+            let text = graphics::Text::new(text.as_str());
+            canvas.draw(&text, graphics::DrawParam::new().dest(glam::Vec2::new(8.0, 48.0)).color(Color::new(1.0, 0.9, 0.4, 1.0)));
+        }
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, input: KeyInput, shared: &mut Shared) -> GameResult<Transition> {
+        match input.keycode {
+            Some(KeyCode::Key1) => self.tool = Tool::Block(Material::Normal),
+            Some(KeyCode::Key2) => self.tool = Tool::Block(Material::Ice),
+            Some(KeyCode::Key3) => self.tool = Tool::Block(Material::Sticky),
+            //PRESSING 4 AGAIN GOES THROUGH THE KINDS OF ENEMIES
+            Some(KeyCode::Key4) => {
+                self.tool = match self.tool {
+                    Tool::Enemy(kind) => {
+                        let next = EnemyKind::ALL.iter().position(|other| *other == kind).map_or(0, |i| (i + 1) % EnemyKind::ALL.len());
+                        Tool::Enemy(EnemyKind::ALL[next])
+                    },
+                    _ => Tool::Enemy(EnemyKind::Turret),
+                };
+            },
+            Some(KeyCode::Key5) => self.tool = Tool::Powerup,
+            Some(KeyCode::Key6) => self.tool = Tool::Spawn,
+            Some(SAVE_KEY) => self.save(shared),
+            Some(PLAYTEST_KEY) => match self.playtest(ctx, shared) {
+                Ok(run) => return Ok(Transition::Push(Box::new(run))),
+                Err(reason) => self.show(format!("CANNOT PLAYTEST: {}", reason)),
+            },
+            Some(PREVIOUS_FILE_KEY) if self.file > 0 && self.may_leave() => self.open(self.file - 1),
+            Some(NEXT_FILE_KEY) if self.file + 1 < self.files.len() && self.may_leave() => self.open(self.file + 1),
+            Some(EDITOR_KEY) | Some(KeyCode::Escape) if self.may_leave() => return Ok(Transition::ReplaceAll(Box::new(Title::new()))),
+            _ => (),
+        }
+        Ok(Transition::None)
+    }
+}
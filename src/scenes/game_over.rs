@@ -0,0 +1,47 @@
+use ggez::{graphics::{Canvas, Color}, input::keyboard::{KeyCode, KeyInput}, Context, GameResult};
+use crate::world::DeathCause;
+use super::{draw_centered, Playing, Scene, Shared, Title, Transition};
+
+//PIXELS IN A METRE OF THE DISTANCE SHOWN TO THE PLAYER, ONE PLAYER HEIGHT
+const PIXELS_PER_METRE: f64 = 64.0;
+
+// The end of a run, with what ended it and how far it got
+pub struct GameOver {
+    cause: DeathCause,
+    distance: f64,
+    score: u64,
+    seed: u64
+}
+
+impl GameOver {
+    pub fn new(cause: DeathCause, distance: f64, score: u64, seed: u64) -> Self {
+        GameOver { cause, distance, score, seed }
+    }
+}
+
+impl Scene for GameOver {
+    fn update(&mut self, _ctx: &mut Context, _shared: &mut Shared) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut Canvas, _shared: &Shared) -> GameResult {
+        draw_centered(canvas, "GAME OVER", 140.0, 48.0, Color::WHITE);
+        draw_centered(canvas, self.cause.describe(), 200.0, 20.0, Color::RED);
+        draw_centered(canvas, &format!("DISTANCE: {:.0} M", self.distance / PIXELS_PER_METRE), 240.0, 20.0, Color::WHITE);
+        draw_centered(canvas, &format!("SCORE: {}", self.score), 270.0, 20.0, Color::WHITE);
+        //SHOW THE SEED SO THE LAYOUT CAN BE REPLAYED
+        draw_centered(canvas, &format!("SEED: {}", self.seed), 300.0, 20.0, Color::WHITE);
+        draw_centered(canvas, "R: RETRY THIS SEED    ENTER: NEW RUN    ESC: TITLE", 360.0, 16.0, Color::WHITE);
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, shared: &mut Shared) -> GameResult<Transition> {
+        Ok(match input.keycode {
+            Some(KeyCode::R) => Transition::Replace(Box::new(Playing::new(self.seed, shared))),
+            Some(KeyCode::Return) => Transition::Replace(Box::new(Playing::new(shared.next_seed(), shared))),
+            Some(KeyCode::Escape) => Transition::Replace(Box::new(Title::new())),
+            Some(KeyCode::Q) => Transition::Quit,
+            _ => Transition::None,
+        })
+    }
+}
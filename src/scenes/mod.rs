@@ -0,0 +1,95 @@
+use ggez::{glam, graphics::{self, Canvas, Color}, input::keyboard::KeyInput, Context, GameResult};
+use std::path::PathBuf;
+use crate::assets::Assets;
+use crate::config::GameConfig;
+use crate::level::Level;
+use crate::camera::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+mod cutscene;
+mod editor;
+mod game_over;
+mod paused;
+mod playing;
+mod settings;
+mod title;
+
+pub use cutscene::Cutscene;
+pub use editor::Editor;
+pub use game_over::GameOver;
+pub use paused::Paused;
+pub use playing::Playing;
+pub use settings::SettingsMenu;
+pub use title::Title;
+
+// Options picked on the settings screen, kept for the whole session
+#[derive(Copy, Clone, Debug)]
+pub struct Settings {
+    pub show_debug: bool,
+    pub play_intro: bool
+}
+
+// State every scene can reach
+pub struct Shared {
+    pub assets: Assets,
+    pub config: GameConfig,
+    pub levels: Vec<Level>,
+    // Where the levels are read from, and where the editor saves them
+    pub level_dir: PathBuf,
+    pub settings: Settings,
+    pub fixed_seed: Option<u64>
+}
+
+impl Shared {
+    // The seed of the next run, pinned by `--seed` or picked at random
+    pub fn next_seed(&self) -> u64 {
+        self.fixed_seed.unwrap_or_else(rand::random)
+    }
+}
+
+//WHAT THE SCENE STACK SHOULD DO AFTER A SCENE HANDLED AN EVENT
+pub enum Transition {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    // Pop this many scenes at once, such as a pause menu together with the run under it
+    PopMany(usize),
+    Replace(Box<dyn Scene>),
+    ReplaceAll(Box<dyn Scene>),
+    Quit
+}
+
+// A screen of the game, only the scene on top of the stack receives updates and keys
+pub trait Scene {
+    fn update(&mut self, ctx: &mut Context, shared: &mut Shared) -> GameResult<Transition>;
+
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, shared: &Shared) -> GameResult;
+
+    fn key_down_event(&mut self, ctx: &mut Context, input: KeyInput, shared: &mut Shared) -> GameResult<Transition>;
+
+    // The tuning file was reloaded while the scene is on the stack
+    fn config_changed(&mut self, _config: &GameConfig) {}
+
+    // Whether the scene below keeps being drawn underneath this one
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+// Draw a line of text centred horizontally around `y`
+pub fn draw_centered(canvas: &mut Canvas, text: &str, y: f32, scale: f32, color: Color) {
+    let mut text = graphics::Text::new(text);
+    text.set_scale(scale).set_layout(graphics::TextLayout::center());
+    canvas.draw(&text, graphics::DrawParam::new().dest(glam::Vec2::new(SCREEN_WIDTH as f32 / 2.0, y)).color(color));
+}
+
+// Darken whatever was drawn so far, behind menus and overlays
+pub fn draw_shade(canvas: &mut Canvas, alpha: f32) {
+    let rect = graphics::Rect::new(0.0, 0.0, SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32);
+    canvas.draw(
+        &graphics::Quad,
+        graphics::DrawParam::new()
+            .dest(rect.point())
+            .scale(rect.size())
+            .color(Color::new(0.0, 0.0, 0.0, alpha)),
+    );
+}
@@ -0,0 +1,42 @@
+use ggez::{graphics::{Canvas, Color}, input::keyboard::{KeyCode, KeyInput}, Context, GameResult};
+use super::{draw_centered, draw_shade, Scene, Shared, Title, Transition};
+
+// Freezes the run underneath it until it is resumed
+pub struct Paused {
+    // The run is a playtest from the editor, leaving it goes back to the editor so its unsaved level is kept
+    playtest: bool
+}
+
+impl Paused {
+    pub fn new(playtest: bool) -> Self {
+        Paused { playtest }
+    }
+}
+
+impl Scene for Paused {
+    fn update(&mut self, _ctx: &mut Context, _shared: &mut Shared) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut Canvas, _shared: &Shared) -> GameResult {
+        draw_shade(canvas, 0.6);
+        draw_centered(canvas, "PAUSED", 180.0, 48.0, Color::WHITE);
+        let options = if self.playtest { "ESC: RESUME    T: EDITOR    Q: QUIT" } else { "ESC: RESUME    T: TITLE    Q: QUIT" };
+        draw_centered(canvas, options, 260.0, 16.0, Color::WHITE);
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, _shared: &mut Shared) -> GameResult<Transition> {
+        Ok(match input.keycode {
+            Some(KeyCode::Escape) | Some(KeyCode::P) => Transition::Pop,
+            Some(KeyCode::T) if self.playtest => Transition::PopMany(2),
+            Some(KeyCode::T) => Transition::ReplaceAll(Box::new(Title::new())),
+            Some(KeyCode::Q) => Transition::Quit,
+            _ => Transition::None,
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
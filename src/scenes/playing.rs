@@ -0,0 +1,345 @@
+use ggez::{glam::{self, Vec2}, graphics::{self, Canvas, Color}, input::keyboard::{KeyCode, KeyInput, KeyMods}, Context, GameResult};
+use crate::camera::{Camera, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::config::GameConfig;
+use crate::entities::{Material, Projectile, Sprite, Weapon};
+use crate::generator;
+use crate::world::{GameEvent, InputFrame, World};
+use crate::level::Level;
+use super::{draw_centered, Editor, GameOver, Paused, Scene, Shared, Transition};
+
+//KEYSTROKES
+const LEFT_KEY: KeyCode = KeyCode::Left;
+const RIGHT_KEY: KeyCode = KeyCode::Right;
+const RUNNING_KEY: KeyMods = KeyMods::SHIFT;
+const JUMP_KEY: KeyCode = KeyCode::Z;
+const SHOOT_KEY: KeyCode = KeyCode::X;
+const DASH_KEY: KeyCode = KeyCode::C;
+const PAUSE_KEY: KeyCode = KeyCode::Escape;
+const DEBUG_KEY: KeyCode = KeyCode::F3;
+const EDITOR_KEY: KeyCode = KeyCode::F2;
+
+//FIXED TIMESTEP
+const TICK_RATE: f64 = 120.0;
+//LONGEST FRAME THAT IS CAUGHT UP ON, SO A HITCH DOES NOT FREEZE THE GAME IN A BURST OF TICKS
+const MAX_FRAME_TIME: f64 = 0.25;
+
+//HOW LONG A NOTICE SUCH AS AN UNLOCKED ABILITY STAYS ON SCREEN, IN SECONDS
+const NOTICE_DURATION: f64 = 3.0;
+
+//HOW FAST THE PLAYER BLINKS WHILE INVULNERABLE, IN SECONDS PER BLINK
+const FLASH_INTERVAL: f64 = 0.1;
+
+//THE BOSS HEALTH BAR ALONG THE BOTTOM OF THE SCREEN
+const BOSS_BAR_WIDTH: f32 = 400.0;
+const BOSS_BAR_HEIGHT: f32 = 12.0;
+
+// The part of the world state that is interpolated between two ticks when drawing, the other moving entities keep their own last position
+#[derive(Copy, Clone, Debug)]
+struct Frame {
+    player_x: f64,
+    player_y: f64,
+    camera: Camera,
+    wall_x: f64,
+    // How far the drawing is from the previous tick towards the current one
+    alpha: f64
+}
+
+impl Frame {
+    fn capture(world: &World) -> Frame {
+        Frame { player_x: world.player.pos_x, player_y: world.player.pos_y, camera: world.camera, wall_x: world.wall_x, alpha: 1.0 }
+    }
+
+    // Blend from `self` (the previous tick) towards `next` (the current tick)
+    fn lerp(&self, next: &Frame, alpha: f64) -> Frame {
+        let mix = |a: f64, b: f64| a + (b - a) * alpha;
+        let mut camera = next.camera;
+        camera.x = mix(self.camera.x, next.camera.x);
+        camera.y = mix(self.camera.y, next.camera.y);
+        Frame { player_x: mix(self.player_x, next.player_x), player_y: mix(self.player_y, next.player_y), camera, wall_x: mix(self.wall_x, next.wall_x), alpha }
+    }
+
+    // Where a point of the world ends up on the canvas
+    fn to_screen(self, x: f64, y: f64) -> Vec2 {
+        let (x, y) = self.camera.to_screen(x, y);
+        glam::Vec2::new(x as f32, y as f32)
+    }
+
+    // Where an entity that moved from `(last_x, last_y)` to `(x, y)` during the tick is drawn
+    fn blend(self, last_x: f64, last_y: f64, x: f64, y: f64) -> Vec2 {
+        self.to_screen(last_x + (x - last_x) * self.alpha, last_y + (y - last_y) * self.alpha)
+    }
+}
+
+// The run itself, translating ggez input into the world and the world into the canvas
+pub struct Playing {
+    world: World,
+    previous: Frame,
+    accumulator: f64,
+    pending_jump: bool,
+    pending_dash: bool,
+    pending_shoot: bool,
+    notice: Option<(String, f64)>,
+    // The level tried out from the editor, the run goes back to the editor instead of ending
+    playtest: Option<Level>
+}
+
+impl Playing {
+    pub fn new(seed: u64, shared: &Shared) -> Self {
+        let world = World::new(seed, shared.config, &shared.levels);
+        let previous = Frame::capture(&world);
+        let notice = Self::level_notice(shared, 0);
+        Playing { world, previous, accumulator: 0.0, pending_jump: false, pending_dash: false, pending_shoot: false, notice, playtest: None }
+    }
+
+    // A run through a single level from the editor, without the other levels in front of it
+    pub fn playtest(seed: u64, level: Level, shared: &Shared) -> Self {
+        let world = World::new(seed, shared.config, std::slice::from_ref(&level));
+        let previous = Frame::capture(&world);
+        Playing { world, previous, accumulator: 0.0, pending_jump: false, pending_dash: false, pending_shoot: false, notice: None, playtest: Some(level) }
+    }
+
+    // The name of an authored level, shown when the player enters it
+    fn level_notice(shared: &Shared, index: usize) -> Option<(String, f64)> {
+        shared.levels.get(index)
+            .filter(|level| !level.name.is_empty())
+            .map(|level| (level.name.to_uppercase(), NOTICE_DURATION))
+    }
+
+    // The colour a weapon pickup is drawn in
+    fn weapon_tint(weapon: Weapon) -> Color {
+        match weapon {
+            Weapon::Blaster => Color::WHITE,
+            Weapon::Shotgun => Color::new(1.0, 0.9, 0.3, 1.0),
+            Weapon::Rifle => Color::new(0.4, 0.8, 1.0, 1.0),
+            Weapon::Launcher => Color::new(1.0, 0.6, 0.2, 1.0),
+        }
+    }
+
+    // Sample the keyboard into the input of a single simulation step
+    fn input(ctx: &Context) -> InputFrame {
+        let k_ctx = &ctx.keyboard;
+        InputFrame {
+            left: k_ctx.is_key_pressed(LEFT_KEY),
+            right: k_ctx.is_key_pressed(RIGHT_KEY),
+            run: k_ctx.is_mod_active(RUNNING_KEY),
+            jump_pressed: k_ctx.is_key_just_pressed(JUMP_KEY),
+            jump_held: k_ctx.is_key_pressed(JUMP_KEY),
+            dash: k_ctx.is_key_just_pressed(DASH_KEY),
+            shoot: k_ctx.is_key_just_pressed(SHOOT_KEY),
+            shoot_held: k_ctx.is_key_pressed(SHOOT_KEY)
+        }
+    }
+}
+
+impl Scene for Playing {
+    // Update loop
+    fn update(&mut self, ctx: &mut Context, shared: &mut Shared) -> GameResult<Transition> {
+        let mut input = Self::input(ctx);
+        // A key press has to survive until a tick consumes it, and must not be repeated by the following ones
+        self.pending_jump |= input.jump_pressed;
+        self.pending_dash |= input.dash;
+        self.pending_shoot |= input.shoot;
+
+        let frame_time = ctx.time.delta().as_secs_f64().min(MAX_FRAME_TIME);
+        if let Some((_, time_left)) = self.notice.as_mut() {
+            *time_left -= frame_time;
+        }
+        self.notice = self.notice.take().filter(|(_, time_left)| *time_left > 0.0);
+
+        let dt = 1.0 / TICK_RATE;
+        self.accumulator += frame_time;
+        while self.accumulator >= dt {
+            self.accumulator -= dt;
+            input.jump_pressed = self.pending_jump;
+            input.dash = self.pending_dash;
+            input.shoot = self.pending_shoot;
+            self.pending_jump = false;
+            self.pending_dash = false;
+            self.pending_shoot = false;
+            self.previous = Frame::capture(&self.world);
+            for event in self.world.step(input, dt) {
+                match event {
+                    GameEvent::PlayerDied(_) if self.playtest.is_some() => return Ok(Transition::Pop),
+                    GameEvent::PlayerDied(cause) => {
+                        return Ok(Transition::Replace(Box::new(GameOver::new(cause, self.world.distance, self.world.score, self.world.seed))));
+                    },
+                    GameEvent::AbilityUnlocked(ability) => {
+                        self.notice = Some((ability.describe().to_string(), NOTICE_DURATION));
+                    },
+                    GameEvent::LifeLost(cause) => {
+                        self.notice = Some((format!("{}, {} LEFT", cause.describe(), self.world.player.lives), NOTICE_DURATION));
+                    },
+                    GameEvent::WeaponCollected(weapon) => {
+                        self.notice = Some((format!("{} PICKED UP", weapon.name().to_uppercase()), NOTICE_DURATION));
+                    },
+                    GameEvent::OutOfAmmo => {
+                        self.notice = Some(("OUT OF AMMO".to_string(), NOTICE_DURATION));
+                    },
+                    GameEvent::BossAppeared => {
+                        self.notice = Some(("A BOSS BLOCKS THE WAY".to_string(), NOTICE_DURATION));
+                    },
+                    GameEvent::BossPhase(_) => {
+                        self.notice = Some(("THE BOSS IS GETTING ANGRY".to_string(), NOTICE_DURATION));
+                    },
+                    GameEvent::BossDefeated => {
+                        self.notice = Some(("BOSS DEFEATED".to_string(), NOTICE_DURATION));
+                    },
+                    GameEvent::LevelEntered(_) if self.playtest.is_some() => (),
+                    GameEvent::LevelEntered(index) => {
+                        self.notice = Self::level_notice(shared, index).or(self.notice.take());
+                    },
+                    _ => (),
+                }
+            }
+        }
+        Ok(Transition::None)
+    }
+
+    // Draw loop
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, shared: &Shared) -> GameResult {
+        let assets = &shared.assets;
+        let world = &self.world;
+        let alpha = self.accumulator * TICK_RATE;
+        let frame = self.previous.lerp(&Frame::capture(world), alpha.min(1.0));
+
+        // DRAW EACH BACKGROUND
+        for item in world.backgrounds.iter() {
+            let dst = frame.to_screen(item.x, item.y);
+            canvas.draw(assets.image(item.image), graphics::DrawParam::new().dest(dst).rotation(item.rotation as f32));
+        }
+
+        // DRAW THE PLAYER, BLINKING WHILE INVULNERABLE
+        let blink = (world.player.invulnerable_timer / FLASH_INTERVAL) as u64 % 2 == 1;
+        if !blink {
+            let player_image = if world.player.direction < 0.0 { Sprite::PlayerLeft } else { Sprite::PlayerRight };
+            let dst = frame.to_screen(frame.player_x, frame.player_y);
+            canvas.draw(assets.image(player_image), graphics::DrawParam::new().dest(dst));
+        }
+
+        // DRAW EACH BLOCK
+        for item in world.blocks.iter().filter(|item| frame.camera.is_visible(item.rect.x, item.rect.width)) {
+            let dst = frame.to_screen(item.rect.x, item.rect.y);
+            let tint = match item.material {
+                Material::Normal => Color::WHITE,
+                Material::Ice => Color::new(0.6, 0.85, 1.0, 1.0),
+                Material::Sticky => Color::new(0.7, 1.0, 0.5, 1.0),
+            };
+            canvas.draw(assets.image(item.image), graphics::DrawParam::new().dest(dst).color(tint));
+        }
+
+        // DRAW EACH BULLEt
+        for item in world.bullets.iter() {
+            let dst = frame.blend(item.last_x, item.last_y, item.x, item.y);
+            let tint = match item.projectile {
+                Projectile::Bullet => Color::WHITE,
+                Projectile::Grenade => Color::new(1.0, 0.6, 0.2, 1.0),
+            };
+            canvas.draw(assets.image(Sprite::Bullet), graphics::DrawParam::new().dest(dst).color(tint));
+        }
+
+        for item in world.enemy_bullets.iter() {
+            let dst = frame.blend(item.last_x, item.last_y, item.x, item.y);
+            canvas.draw(assets.image(item.image), graphics::DrawParam::new().dest(dst));
+        }
+
+        for item in world.enemies.iter() {
+            let dst = frame.blend(item.last_x, item.last_y, item.x, item.y);
+            let (image, rotation) = item.kind.looks(item.direction);
+            canvas.draw(assets.image(image), graphics::DrawParam::new().dest(dst).rotation(rotation));
+        }
+
+        if let Some(boss) = &world.boss {
+            let dst = frame.blend(boss.last_x, boss.last_y, boss.x, boss.y);
+            let image = if boss.direction < 0.0 { Sprite::DevilLeft } else { Sprite::DevilRight };
+            canvas.draw(assets.image(image), graphics::DrawParam::new().dest(dst).scale(Vec2::splat(2.0)));
+        }
+
+        for item in world.powerups.iter() {
+            let dst = frame.to_screen(item.x, item.y);
+            canvas.draw(assets.image(item.image), graphics::DrawParam::new().dest(dst));
+        }
+
+        for item in world.weapon_pickups.iter() {
+            let dst = frame.to_screen(item.x, item.y);
+            canvas.draw(assets.image(Sprite::Bullet), graphics::DrawParam::new().dest(dst).color(Self::weapon_tint(item.weapon)));
+            let mut label = graphics::Text::new(item.weapon.name().to_uppercase());
+            label.set_scale(12.0).set_layout(graphics::TextLayout::center());
+            canvas.draw(&label, graphics::DrawParam::new().dest(dst + Vec2::new(16.0, -8.0)).color(Color::WHITE));
+        }
+
+        //DRAW THE WALL
+        let dst: Vec2 = glam::Vec2::new(frame.camera.to_screen(frame.wall_x, 0.0).0 as f32, 0.0);
+        canvas.draw(assets.image(Sprite::Wall), graphics::DrawParam::new().dest(dst));
+
+        //SCORE, HEALTH, LIVES AND THE CURRENT WEAPON WITH ITS AMMO
+        let weapon = match world.config.weapons.stats(world.player.weapon).ammo {
+            0 => world.player.weapon.name().to_uppercase(),
+            _ => format!("{} x{}", world.player.weapon.name().to_uppercase(), world.player.ammo),
+        };
+        let mut score = graphics::Text::new(format!("SCORE: {}\nHEALTH: {}/{}\nLIVES: {}\nWEAPON: {}", world.score, world.player.health, world.config.health.max_health, world.player.lives, weapon));
+        score.set_layout(graphics::TextLayout { h_align: graphics::TextAlign::End, v_align: graphics::TextAlign::Begin });
+        canvas.draw(&score, graphics::DrawParam::new().dest(glam::Vec2::new(SCREEN_WIDTH as f32 - 8.0, 8.0)).color(Color::WHITE));
+
+        //BOSS HEALTH BAR
+        if let Some(boss) = &world.boss {
+            let (x, y) = ((SCREEN_WIDTH as f32 - BOSS_BAR_WIDTH) / 2.0, SCREEN_HEIGHT as f32 - 2.0 * BOSS_BAR_HEIGHT);
+            let filled = BOSS_BAR_WIDTH * boss.health as f32 / boss.max_health as f32;
+            canvas.draw(&graphics::Quad, graphics::DrawParam::new().dest_rect(graphics::Rect::new(x, y, BOSS_BAR_WIDTH, BOSS_BAR_HEIGHT)).color(Color::new(0.2, 0.2, 0.2, 1.0)));
+            canvas.draw(&graphics::Quad, graphics::DrawParam::new().dest_rect(graphics::Rect::new(x, y, filled, BOSS_BAR_HEIGHT)).color(Color::RED));
+            draw_centered(canvas, "BOSS", y - 24.0, 20.0, Color::WHITE);
+        }
+
+        //NOTICE, SUCH AS A FRESHLY UNLOCKED ABILITY
+        if let Some((text, _)) = &self.notice {
+            draw_centered(canvas, text, 48.0, 24.0, Color::WHITE);
+        }
+
+        //DEBUG OVERLAY WITH THE LIVE ENTITY COUNTS
+        if shared.settings.show_debug {
+            let counts = world.entity_counts();
+            let text = graphics::Text::new(format!(
+                "FPS: {:.0}\nBLOCKS: {}\nBULLETS: {}\nBACKGROUNDS: {}\nPOWERUPS: {}\nENEMIES: {}\nENEMY BULLETS: {}\nWEAPONS: {}\nCHUNK: {:?}\nDIFFICULTY: {:.2}",
+                ctx.time.fps(), counts.blocks, counts.bullets, counts.backgrounds, counts.powerups, counts.enemies, counts.enemy_bullets, counts.weapon_pickups,
+                world.last_chunk, generator::difficulty(&world.config, world.distance)
+            ));
+            canvas.draw(&text, graphics::DrawParam::new().dest(glam::Vec2::new(8.0, 8.0)).color(Color::WHITE));
+        }
+
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, shared: &mut Shared) -> GameResult<Transition> {
+        match input.keycode {
+            Some(KeyCode::Q) => {
+                return Ok(Transition::Quit);
+            },
+            Some(KeyCode::R) => {
+                let run = match &self.playtest {
+                    Some(level) => Playing::playtest(shared.next_seed(), level.clone(), shared),
+                    None => Playing::new(shared.next_seed(), shared),
+                };
+                return Ok(Transition::Replace(Box::new(run)));
+            },
+            Some(EDITOR_KEY) if self.playtest.is_some() => {
+                return Ok(Transition::Pop);
+            },
+            Some(EDITOR_KEY) => {
+                return Ok(Transition::Replace(Box::new(Editor::new(shared))));
+            },
+            Some(PAUSE_KEY) | Some(KeyCode::P) => {
+                return Ok(Transition::Push(Box::new(Paused::new(self.playtest.is_some()))));
+            },
+            Some(DEBUG_KEY) => {
+                shared.settings.show_debug = !shared.settings.show_debug;
+            },
+            _ => (),
+        }
+        Ok(Transition::None)
+    }
+
+    fn config_changed(&mut self, config: &GameConfig) {
+        self.world.config = *config;
+        self.world.camera.configure(&config.camera);
+        self.notice = Some(("TUNING RELOADED".to_string(), NOTICE_DURATION));
+    }
+}
@@ -0,0 +1,55 @@
+use ggez::{graphics::{Canvas, Color}, input::keyboard::{KeyCode, KeyInput}, Context, GameResult};
+use super::{draw_centered, Scene, Settings, Shared, Transition};
+
+//THE OPTIONS LISTED ON THE SCREEN, IN ORDER
+const OPTIONS: [&str; 2] = ["SHOW DEBUG OVERLAY", "PLAY THE INTRO"];
+
+// Toggles for the session wide settings
+pub struct SettingsMenu {
+    selected: usize
+}
+
+impl SettingsMenu {
+    pub fn new() -> Self {
+        SettingsMenu { selected: 0 }
+    }
+
+    fn option(settings: &mut Settings, index: usize) -> &mut bool {
+        match index {
+            0 => &mut settings.show_debug,
+            _ => &mut settings.play_intro,
+        }
+    }
+}
+
+impl Scene for SettingsMenu {
+    fn update(&mut self, _ctx: &mut Context, _shared: &mut Shared) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut Canvas, shared: &Shared) -> GameResult {
+        let mut settings = shared.settings;
+        draw_centered(canvas, "SETTINGS", 120.0, 48.0, Color::WHITE);
+        for (index, name) in OPTIONS.iter().enumerate() {
+            let value = if *Self::option(&mut settings, index) { "ON" } else { "OFF" };
+            let color = if index == self.selected { Color::YELLOW } else { Color::WHITE };
+            draw_centered(canvas, &format!("{}: {}", name, value), 220.0 + 32.0 * index as f32, 20.0, color);
+        }
+        draw_centered(canvas, "UP/DOWN: SELECT    ENTER: TOGGLE    ESC: BACK", 400.0, 16.0, Color::WHITE);
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, shared: &mut Shared) -> GameResult<Transition> {
+        match input.keycode {
+            Some(KeyCode::Up) => self.selected = (self.selected + OPTIONS.len() - 1) % OPTIONS.len(),
+            Some(KeyCode::Down) => self.selected = (self.selected + 1) % OPTIONS.len(),
+            Some(KeyCode::Return) | Some(KeyCode::Left) | Some(KeyCode::Right) => {
+                let option = Self::option(&mut shared.settings, self.selected);
+                *option = !*option;
+            },
+            Some(KeyCode::Escape) => return Ok(Transition::Pop),
+            _ => (),
+        }
+        Ok(Transition::None)
+    }
+}
@@ -0,0 +1,43 @@
+use ggez::{graphics::{Canvas, Color}, input::keyboard::{KeyCode, KeyInput}, Context, GameResult};
+use super::{draw_centered, Cutscene, Editor, Playing, Scene, SettingsMenu, Shared, Transition};
+
+// The start screen
+pub struct Title;
+
+impl Title {
+    pub fn new() -> Self {
+        Title
+    }
+}
+
+impl Scene for Title {
+    fn update(&mut self, _ctx: &mut Context, _shared: &mut Shared) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut Canvas, _shared: &Shared) -> GameResult {
+        draw_centered(canvas, "TIMELESS", 150.0, 64.0, Color::WHITE);
+        draw_centered(canvas, "ENTER: START", 260.0, 20.0, Color::WHITE);
+        draw_centered(canvas, "S: SETTINGS", 290.0, 20.0, Color::WHITE);
+        draw_centered(canvas, "F2: LEVEL EDITOR", 320.0, 20.0, Color::WHITE);
+        draw_centered(canvas, "Q: QUIT", 350.0, 20.0, Color::WHITE);
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, shared: &mut Shared) -> GameResult<Transition> {
+        Ok(match input.keycode {
+            Some(KeyCode::Return) => {
+                let run = Box::new(Playing::new(shared.next_seed(), shared));
+                if shared.settings.play_intro {
+                    Transition::Replace(Box::new(Cutscene::intro(run)))
+                } else {
+                    Transition::Replace(run)
+                }
+            },
+            Some(KeyCode::S) => Transition::Push(Box::new(SettingsMenu::new())),
+            Some(KeyCode::F2) => Transition::Replace(Box::new(Editor::new(shared))),
+            Some(KeyCode::Q) | Some(KeyCode::Escape) => Transition::Quit,
+            _ => Transition::None,
+        })
+    }
+}
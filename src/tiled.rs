@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use serde::Deserialize;
+use serde_json::Value;
+use crate::entities::{EnemyKind, Material, Sprite};
+use crate::generator::COLUMN_WIDTH;
+use crate::level::{Level, LevelBlock, LevelEnemy, Point};
+
+//THE TOP BITS OF A TILE ID STORE HOW THE TILE IS FLIPPED, WHICH DOES NOT MATTER FOR A BLOCK
+const FLIP_FLAGS: u32 = 0xF000_0000;
+
+// A Tiled map reduced to what a level needs, shared by the JSON and the XML formats
+#[derive(Default)]
+struct Map {
+    tile_width: f64,
+    tile_height: f64,
+    properties: HashMap<String, String>,
+    tilesets: Vec<Tileset>,
+    layers: Vec<Layer>
+}
+
+#[derive(Default)]
+struct Tileset {
+    first_gid: u32,
+    // The image of a tileset made of a single picture
+    image: Option<String>,
+    tiles: HashMap<u32, Tile>
+}
+
+#[derive(Default)]
+struct Tile {
+    image: Option<String>,
+    properties: HashMap<String, String>
+}
+
+enum Layer {
+    Tiles { width: usize, offset_x: f64, offset_y: f64, data: Vec<u32> },
+    Objects(Vec<Object>)
+}
+
+struct Object {
+    kind: String,
+    x: f64,
+    y: f64,
+    height: f64,
+    // Tile objects are placed by their bottom left corner instead of the top left one
+    is_tile: bool
+}
+
+// Build a level from the text of a Tiled map, `extension` tells the JSON (.tmj) and XML (.tmx) formats apart
+pub fn import(text: &str, extension: &str) -> Result<Level, String> {
+    let map = match extension {
+        "tmj" | "json" => parse_json(text)?,
+        "tmx" => parse_xml(text)?,
+        _ => return Err(format!("unknown map format .{}", extension)),
+    };
+    map.into_level()
+}
+
+impl Map {
+    // The tile a global tile id refers to, with the image of its tileset when the tile has none of its own
+    fn tile(&self, gid: u32) -> Option<(Option<&String>, Option<&Tile>)> {
+        let tileset = self.tilesets.iter().filter(|tileset| tileset.first_gid <= gid).max_by_key(|tileset| tileset.first_gid)?;
+        let tile = tileset.tiles.get(&(gid - tileset.first_gid));
+        let image = tile.and_then(|tile| tile.image.as_ref()).or(tileset.image.as_ref());
+        Some((image, tile))
+    }
+
+    fn into_level(self) -> Result<Level, String> {
+        if self.tile_width <= 0.0 || self.tile_height <= 0.0 {
+            return Err("the tile size must be positive".to_string());
+        }
+        // One tile of the map is one block of the game, whatever its size in Tiled
+        let scale_x = COLUMN_WIDTH / self.tile_width;
+        let scale_y = COLUMN_WIDTH / self.tile_height;
+        let number = |name: &str| -> Result<Option<f64>, String> {
+            self.properties.get(name)
+                .map(|value| value.parse::<f64>().map_err(|_| format!("map property {} must be a number", name)))
+                .transpose()
+        };
+
+        let mut level = Level {
+            name: self.properties.get("name").cloned().unwrap_or_default(),
+            spawn: None,
+            wall_start: number("wall_start")?,
+            exit_y: number("exit_y")?,
+            blocks: vec![],
+            enemies: vec![],
+            powerups: vec![]
+        };
+
+        for layer in self.layers.iter() {
+            match layer {
+                //EVERY TILE BECOMES A BLOCK DRAWN WITH THE TILE IMAGE
+                Layer::Tiles { width, offset_x, offset_y, data } => {
+                    for (i, gid) in data.iter().enumerate() {
+                        let gid = gid & !FLIP_FLAGS;
+                        if gid == 0 {
+                            continue;
+                        }
+                        let (image, tile) = self.tile(gid).ok_or_else(|| format!("tile {} belongs to no tileset", gid))?;
+                        let image = image.ok_or_else(|| format!("tile {} has no image", gid))?;
+                        let file_name = image.rsplit(['/', '\\']).next().unwrap_or(image);
+                        let image = Sprite::from_file_name(file_name).ok_or_else(|| format!("{} is not one of the game's images", file_name))?;
+                        let material = match tile.and_then(|tile| tile.properties.get("material")) {
+                            Some(name) => Material::from_name(name).ok_or_else(|| format!("unknown material {}", name))?,
+                            None => Material::Normal,
+                        };
+                        let (column, row) = ((i % width) as f64, (i / width) as f64);
+                        level.blocks.push(LevelBlock {
+                            x: (column * self.tile_width + offset_x) * scale_x,
+                            y: (row * self.tile_height + offset_y) * scale_y,
+                            material,
+                            image
+                        });
+                    }
+                },
+                //OBJECTS ARE TOLD APART BY THEIR CLASS
+                Layer::Objects(objects) => {
+                    for object in objects.iter() {
+                        let y = if object.is_tile { object.y - object.height } else { object.y };
+                        let point = Point { x: object.x * scale_x, y: y * scale_y };
+                        if let Some(kind) = EnemyKind::from_name(&object.kind) {
+                            level.enemies.push(LevelEnemy { x: point.x, y: point.y, kind });
+                            continue;
+                        }
+                        match object.kind.to_lowercase().as_str() {
+                            "enemy" => level.enemies.push(LevelEnemy { x: point.x, y: point.y, kind: EnemyKind::Turret }),
+                            "powerup" | "clock" => level.powerups.push(point),
+                            "spawn" | "player" => level.spawn = Some(point),
+                            // Objects without a class, such as tiles placed for decoration, are left out
+                            "" => (),
+                            kind => {
+                                let kinds: Vec<&str> = EnemyKind::ALL.iter().map(|kind| kind.name()).collect();
+                                return Err(format!("unknown object class \"{}\", expected enemy, {}, powerup or spawn", kind, kinds.join(", ")));
+                            },
+                        }
+                    }
+                },
+            }
+        }
+        Ok(level)
+    }
+}
+
+//<JSON (.tmj)>
+#[derive(Deserialize)]
+struct JsonMap {
+    tilewidth: f64,
+    tileheight: f64,
+    #[serde(default)]
+    infinite: bool,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>
+}
+
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    value: Value
+}
+
+#[derive(Deserialize)]
+struct JsonTileset {
+    firstgid: u32,
+    source: Option<String>,
+    image: Option<String>,
+    #[serde(default)]
+    tiles: Vec<JsonTile>
+}
+
+#[derive(Deserialize)]
+struct JsonTile {
+    id: u32,
+    image: Option<String>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>
+}
+
+#[derive(Deserialize)]
+struct JsonLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    width: usize,
+    #[serde(default)]
+    offsetx: f64,
+    #[serde(default)]
+    offsety: f64,
+    data: Option<Value>,
+    encoding: Option<String>,
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>
+}
+
+#[derive(Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    x: f64,
+    y: f64,
+    #[serde(default)]
+    height: f64,
+    gid: Option<u32>
+}
+
+fn json_properties(properties: &[JsonProperty]) -> HashMap<String, String> {
+    properties.iter().map(|property| {
+        let value = match &property.value {
+            Value::String(text) => text.clone(),
+            value => value.to_string(),
+        };
+        (property.name.clone(), value)
+    }).collect()
+}
+
+fn parse_json(text: &str) -> Result<Map, String> {
+    let json: JsonMap = serde_json::from_str(text).map_err(|error| error.to_string())?;
+    if json.infinite {
+        return Err("infinite maps are not supported".to_string());
+    }
+
+    let mut map = Map { tile_width: json.tilewidth, tile_height: json.tileheight, properties: json_properties(&json.properties), ..Map::default() };
+    for tileset in json.tilesets.iter() {
+        if tileset.source.is_some() {
+            return Err("external tilesets are not supported, embed the tileset in the map".to_string());
+        }
+        let tiles = tileset.tiles.iter().map(|tile| (tile.id, Tile { image: tile.image.clone(), properties: json_properties(&tile.properties) })).collect();
+        map.tilesets.push(Tileset { first_gid: tileset.firstgid, image: tileset.image.clone(), tiles });
+    }
+    json_layers(&json.layers, 0.0, 0.0, &mut map.layers)?;
+    Ok(map)
+}
+
+// Flatten the layers, group layers pass their offset on to the layers inside them
+fn json_layers(layers: &[JsonLayer], offset_x: f64, offset_y: f64, out: &mut Vec<Layer>) -> Result<(), String> {
+    for layer in layers.iter() {
+        let (offset_x, offset_y) = (offset_x + layer.offsetx, offset_y + layer.offsety);
+        match layer.kind.as_str() {
+            "tilelayer" => {
+                if layer.encoding.as_deref().is_some_and(|encoding| encoding != "csv") {
+                    return Err("tile layers must use the CSV layer format".to_string());
+                }
+                let data = match &layer.data {
+                    Some(Value::Array(values)) => values.iter().map(|value| value.as_u64().map(|gid| gid as u32)).collect::<Option<Vec<u32>>>(),
+                    _ => None,
+                }.ok_or("tile layer data must be a list of tile ids")?;
+                out.push(Layer::Tiles { width: layer.width.max(1), offset_x, offset_y, data });
+            },
+            "objectgroup" => {
+                let objects = layer.objects.iter().map(|object| {
+                    let kind = [&object.class, &object.kind, &object.name].into_iter().find(|kind| !kind.is_empty()).cloned().unwrap_or_default();
+                    Object { kind, x: object.x + offset_x, y: object.y + offset_y, height: object.height, is_tile: object.gid.is_some() }
+                }).collect();
+                out.push(Layer::Objects(objects));
+            },
+            "group" => json_layers(&layer.layers, offset_x, offset_y, out)?,
+            _ => (),
+        }
+    }
+    Ok(())
+}
+//</JSON (.tmj)>
+
+//<XML (.tmx)>
+fn attribute<T: std::str::FromStr>(node: roxmltree::Node, name: &str) -> Option<T> {
+    node.attribute(name).and_then(|value| value.parse().ok())
+}
+
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn xml_properties(node: roxmltree::Node) -> HashMap<String, String> {
+    child(node, "properties").map(|properties| {
+        properties.children().filter(|property| property.has_tag_name("property")).filter_map(|property| {
+            let value = property.attribute("value").or_else(|| property.text()).unwrap_or_default();
+            Some((property.attribute("name")?.to_string(), value.to_string()))
+        }).collect()
+    }).unwrap_or_default()
+}
+
+fn parse_xml(text: &str) -> Result<Map, String> {
+    let document = roxmltree::Document::parse(text).map_err(|error| error.to_string())?;
+    let root = document.root_element();
+    if attribute::<u32>(root, "infinite") == Some(1) {
+        return Err("infinite maps are not supported".to_string());
+    }
+
+    let mut map = Map {
+        tile_width: attribute(root, "tilewidth").ok_or("the map has no tile width")?,
+        tile_height: attribute(root, "tileheight").ok_or("the map has no tile height")?,
+        properties: xml_properties(root),
+        ..Map::default()
+    };
+    for tileset in root.children().filter(|node| node.has_tag_name("tileset")) {
+        if tileset.attribute("source").is_some() {
+            return Err("external tilesets are not supported, embed the tileset in the map".to_string());
+        }
+        let image = child(tileset, "image").and_then(|image| image.attribute("source")).map(str::to_string);
+        let tiles = tileset.children().filter(|node| node.has_tag_name("tile")).filter_map(|tile| {
+            let image = child(tile, "image").and_then(|image| image.attribute("source")).map(str::to_string);
+            Some((attribute(tile, "id")?, Tile { image, properties: xml_properties(tile) }))
+        }).collect();
+        map.tilesets.push(Tileset { first_gid: attribute(tileset, "firstgid").ok_or("a tileset has no firstgid")?, image, tiles });
+    }
+    xml_layers(root, 0.0, 0.0, &mut map.layers)?;
+    Ok(map)
+}
+
+// Flatten the layers, group layers pass their offset on to the layers inside them
+fn xml_layers(parent: roxmltree::Node, offset_x: f64, offset_y: f64, out: &mut Vec<Layer>) -> Result<(), String> {
+    for layer in parent.children().filter(|node| node.is_element()) {
+        let offset_x = offset_x + attribute(layer, "offsetx").unwrap_or(0.0);
+        let offset_y = offset_y + attribute(layer, "offsety").unwrap_or(0.0);
+        match layer.tag_name().name() {
+            "layer" => {
+                let data = child(layer, "data").ok_or("a tile layer has no data")?;
+                let gids = match data.attribute("encoding") {
+                    Some("csv") => data.text().unwrap_or_default()
+                        .split(',')
+                        .map(|gid| gid.trim().parse::<u32>().ok())
+                        .collect::<Option<Vec<u32>>>()
+                        .ok_or("tile layer data must be a list of tile ids")?,
+                    None => data.children().filter(|tile| tile.has_tag_name("tile")).map(|tile| attribute(tile, "gid").unwrap_or(0)).collect(),
+                    Some(_) => return Err("tile layers must use the CSV layer format".to_string()),
+                };
+                out.push(Layer::Tiles { width: attribute(layer, "width").unwrap_or(1).max(1), offset_x, offset_y, data: gids });
+            },
+            "objectgroup" => {
+                let objects = layer.children().filter(|node| node.has_tag_name("object")).map(|object| {
+                    let kind = ["class", "type", "name"].into_iter().find_map(|name| object.attribute(name).filter(|kind| !kind.is_empty())).unwrap_or_default();
+                    Object {
+                        kind: kind.to_string(),
+                        x: attribute(object, "x").unwrap_or(0.0) + offset_x,
+                        y: attribute(object, "y").unwrap_or(0.0) + offset_y,
+                        height: attribute(object, "height").unwrap_or(0.0),
+                        is_tile: object.attribute("gid").is_some()
+                    }
+                }).collect();
+                out.push(Layer::Objects(objects));
+            },
+            "group" => xml_layers(layer, offset_x, offset_y, out)?,
+            _ => (),
+        }
+    }
+    Ok(())
+}
+//</XML (.tmx)>
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A one tile map with a tileset of one block, the tile's properties and an object layer given as JSON
+    fn map(tile_properties: &str, objects: &str) -> String {
+        format!(r#"{{
+            "tilewidth": 32, "tileheight": 32,
+            "tilesets": [{{ "firstgid": 1, "tiles": [{{ "id": 0, "image": "block_one.png", "properties": [{}] }}] }}],
+            "layers": [
+                {{ "type": "tilelayer", "width": 1, "data": [1] }},
+                {{ "type": "objectgroup", "objects": [{}] }}
+            ]
+        }}"#, tile_properties, objects)
+    }
+
+    #[test]
+    fn json_and_xml_examples_give_the_same_level() {
+        let json = import(include_str!("../resources/tiled/example.tmj"), "tmj").unwrap();
+        let xml = import(include_str!("../resources/tiled/example.tmx"), "tmx").unwrap();
+        assert_eq!(format!("{:?}", json), format!("{:?}", xml));
+        assert_eq!(json.blocks.len(), 6);
+        assert_eq!(json.blocks.iter().filter(|block| block.material == Material::Ice).count(), 1);
+        assert_eq!(json.enemies.len(), 1);
+        assert_eq!(json.powerups.len(), 1);
+        assert!(json.spawn.is_some());
+        assert!(json.validate().is_ok());
+    }
+
+    #[test]
+    fn material_property_sets_the_block_material() {
+        let level = import(&map(r#"{ "name": "material", "value": "sticky" }"#, ""), "tmj").unwrap();
+        assert_eq!(level.blocks[0].material, Material::Sticky);
+        let level = import(&map("", ""), "tmj").unwrap();
+        assert_eq!(level.blocks[0].material, Material::Normal);
+        let error = import(&map(r#"{ "name": "material", "value": "lava" }"#, ""), "tmj").unwrap_err();
+        assert!(error.contains("lava"));
+    }
+
+    #[test]
+    fn unknown_object_class_lists_every_enemy_kind() {
+        let error = import(&map("", r#"{ "class": "dragon", "x": 0, "y": 0 }"#), "tmj").unwrap_err();
+        assert!(error.contains("dragon"));
+        for kind in EnemyKind::ALL {
+            assert!(error.contains(kind.name()), "{} is missing from: {}", kind.name(), error);
+        }
+    }
+
+    #[test]
+    fn tile_object_without_a_class_is_left_out() {
+        let level = import(&map("", r#"{ "gid": 1, "x": 0, "y": 32, "height": 32 }, { "class": "walker", "gid": 1, "x": 64, "y": 32, "height": 32 }"#), "tmj").unwrap();
+        assert_eq!(level.enemies.len(), 1);
+        assert_eq!((level.enemies[0].x, level.enemies[0].y, level.enemies[0].kind), (256.0, 0.0, EnemyKind::Walker));
+    }
+}
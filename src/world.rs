@@ -0,0 +1,1129 @@
+use std::f64::consts::PI;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use crate::config::{BulletPattern, EnemiesConfig, GameConfig};
+use crate::camera::{Camera, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::collision::Rectangle;
+use crate::generator::{self, Chunk, ChunkKind, COLUMN_WIDTH};
+use crate::level::{Level, Point};
+use crate::entities::{Ability, Background, Block, Boss, Bullet, Enemy, EnemyKind, EnemyState, Enemy_Bullet, Material, Player, Powerup, Projectile, Sprite, Weapon, WeaponPickup, BOSS_SIZE};
+
+//SETUP
+
+//PLAYER DATA INITIALIZATION
+const PLAYER_INITIAL_X: f64 = 45.0;
+const PLAYER_INITIAL_Y: f64 = 0.0;
+const PLAYER_INITIAL_DIRECTION: f64 = 1.0;
+const PLAYER_INITIAL_STANDING: bool = false;
+const PLAYER_INITIAL_VERTICAL_SPEED: f64 = 0.0;
+const PLAYER_INITIAL_HORIZONTAL_SPEED: f64 = 0.0;
+const PLAYER_INITIAL_COLLIDES_LEFT: bool = false;
+const PLAYER_INITIAL_COLLIDES_RIGHT: bool = false;
+//const HORIZONTAL_DECELERATION_RATE: f32 = 1.0; <-- sliding mechanique
+
+//MAX SPEED
+//const MAXIMAL_HORIZONTAL_SPEED_LIMIT_CONSTANT: f64 = 500.0;
+
+//THE REST OF THE TUNING VALUES ARE READ FROM resources/config.toml, SEE config.rs
+
+//WORLD DATA INITIALIZATION
+const INITIAL_WORLD_SPEED_MULTIPLIER: f64 = 1.0;
+
+//THE GROUND THE ENDLESS TERRAIN STARTS FROM WHEN NO LEVEL COMES BEFORE IT
+const INITIAL_GROUND_Y: f64 = 320.0;
+
+//WORLD GENERATION, HOW FAR AHEAD OF THE CAMERA TERRAIN IS LAID DOWN
+const GENERATION_LEAD: f64 = 2.0 * SCREEN_WIDTH;
+
+//THE RUN ENDS ONCE THE PLAYER HAS FALLEN BELOW THE BOTTOM OF THE SCREEN
+const KILL_PLANE_Y: f64 = SCREEN_HEIGHT;
+
+//DESPAWN, HOW FAR BEHIND THE VIEW AN ENTITY IS KEPT AROUND
+const DESPAWN_DISTANCE: f64 = 2.0 * SCREEN_WIDTH;
+
+//BOSS FIGHTS, THE HEIGHT OF THE ARENA WALLS IN BLOCKS AND WHERE THE BOSS HOVERS ABOVE THE GROUND
+const ARENA_WALL_HEIGHT: usize = 5;
+const BOSS_HOVER_HEIGHT: f64 = 288.0;
+const BOSS_BOB_HEIGHT: f64 = 16.0;
+//HOW CLOSE THE BOSS DRIFTS TO THE EDGES OF THE SCREEN
+const BOSS_MARGIN: f64 = 32.0;
+
+//GRENADES ARE THROWN A LITTLE UPWARDS
+const GRENADE_LAUNCH_ANGLE: f64 = PI / 6.0;
+
+//THE INPUT SAMPLED FOR A SINGLE STEP OF THE SIMULATION
+#[derive(Copy, Clone, Debug, Default)]
+pub struct InputFrame {
+    pub left: bool,
+    pub right: bool,
+    pub run: bool,
+    pub jump_pressed: bool,
+    pub jump_held: bool,
+    pub dash: bool,
+    pub shoot: bool,
+    pub shoot_held: bool
+}
+
+//NUMBER OF LIVE ENTITIES, FOR THE DEBUG OVERLAY
+#[derive(Copy, Clone, Debug, Default)]
+pub struct EntityCounts {
+    pub blocks: usize,
+    pub bullets: usize,
+    pub backgrounds: usize,
+    pub powerups: usize,
+    pub enemies: usize,
+    pub enemy_bullets: usize,
+    pub weapon_pickups: usize
+}
+
+//WHAT ENDED THE RUN
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    Wall,
+    Shot,
+    Touched,
+    Fell
+}
+
+impl DeathCause {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            DeathCause::Wall => "EATEN BY THE WALL",
+            DeathCause::Shot => "SHOT DOWN",
+            DeathCause::Touched => "CAUGHT BY AN ENEMY",
+            DeathCause::Fell => "FELL INTO A PIT",
+        }
+    }
+}
+
+//WHAT HAPPENED DURING A SINGLE STEP OF THE SIMULATION
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    Jumped,
+    WallJumped,
+    Dashed,
+    AbilityUnlocked(Ability),
+    LevelEntered(usize),
+    HeadBump,
+    Shot,
+    PowerupCollected,
+    EnemyFired,
+    EnemyHit,
+    EnemyKilled,
+    EnemyBulletShotDown,
+    PlayerHurt,
+    LifeLost(DeathCause),
+    WeaponCollected(Weapon),
+    OutOfAmmo,
+    BossAppeared,
+    BossPhase(u32),
+    BossDefeated,
+    PlayerDied(DeathCause)
+}
+
+//THE ARENA OF A BOSS FIGHT, LAID DOWN AT A DISTANCE MILESTONE
+struct Arena {
+    x: f64,
+    ground_y: f64,
+    // The blocks shutting the player in while the fight lasts
+    walls: Vec<Rectangle>,
+    // How far the camera could scroll before the fight
+    camera_limits: (Option<f64>, Option<f64>)
+}
+
+// Game State, independent from the window and the renderer
+pub struct World {
+    pub player: Player,
+    pub speed: f64,
+    pub blocks: Vec<Block>,
+    pub camera: Camera,
+    pub wall_x: f64,
+    block_id: f64,
+    last_ground_y: f64,
+    pub last_chunk: ChunkKind,
+    wall_speed: f64,
+    pub bullets: Vec<Bullet>,
+    pub backgrounds: Vec<Background>,
+    background_counter: f64,
+    pub powerups: Vec<Powerup>,
+    pub enemies: Vec<Enemy>,
+    powerup_counter: f64,
+    enemy_counter: f64,
+    speed_counter: f64,
+    clear_powerups: bool,
+    pub enemy_bullets: Vec<Enemy_Bullet>,
+    pub weapon_pickups: Vec<WeaponPickup>,
+    weapon_counter: f64,
+    pub seed: u64,
+    rng: StdRng,
+    start_x: f64,
+    level_starts: Vec<f64>,
+    level: usize,
+    pub distance: f64,
+    pub score: u64,
+    arena: Option<Arena>,
+    pub boss: Option<Boss>,
+    next_boss_distance: f64,
+    pub death: Option<DeathCause>,
+    pub config: GameConfig
+}
+
+impl World {
+    // Initial State of the world, every random choice is drawn from `seed` and the authored `levels` come first
+    pub fn new(seed: u64, config: GameConfig, levels: &[Level]) -> World {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let first_level = levels.first();
+        let spawn = first_level.and_then(|level| level.spawn).unwrap_or(Point { x: PLAYER_INITIAL_X, y: PLAYER_INITIAL_Y });
+        let mut player = Player::new(
+            spawn.x, spawn.y, PLAYER_INITIAL_DIRECTION, PLAYER_INITIAL_STANDING, PLAYER_INITIAL_VERTICAL_SPEED, PLAYER_INITIAL_HORIZONTAL_SPEED, PLAYER_INITIAL_COLLIDES_RIGHT, PLAYER_INITIAL_COLLIDES_LEFT
+        );
+        for ability in Ability::ALL {
+            player.abilities.set(ability, config.abilities.unlock_distance(ability) <= 0.0);
+        }
+        player.health = config.health.max_health;
+        player.lives = config.health.lives;
+
+        let bullets: Vec<Bullet> = vec![];
+        let backgrounds: Vec<Background> = vec![
+            Self::randomly_generate_background(&mut rng, 0.0)
+        ];
+        let powerups: Vec<Powerup> = vec![
+            Self::randomly_generate_powerups(&mut rng, 0.0)
+        ];
+        let enemies: Vec<Enemy> = vec![
+            Self::randomly_generate_enemy(&mut rng, 0.0, &config.enemies)
+        ];
+        let enemy_bullets: Vec<Enemy_Bullet> = vec![];
+        let wall_x = first_level.and_then(|level| level.wall_start).unwrap_or(config.wall.start_x);
+        //THE VIEW STARTS ON THE SPAWN, OTHERWISE ITS FIRST CATCH UP WOULD DRAG THE WALL ALONG ONTO THE PLAYER
+        let mut camera = Camera::with_config(&config.camera);
+        camera.snap_to(spawn.x, spawn.y);
+        let mut world = World { player, speed: INITIAL_WORLD_SPEED_MULTIPLIER, blocks: vec![], camera, wall_x, block_id: 0.0, last_ground_y: INITIAL_GROUND_Y, last_chunk: ChunkKind::Flat, wall_speed: config.wall.speed, bullets, backgrounds, background_counter: config.spawn.background_spacing, powerups, enemies, powerup_counter: config.spawn.powerup_spacing, enemy_counter: config.spawn.enemy_spacing, speed_counter: config.powerup.slow_distance, clear_powerups: false, enemy_bullets, weapon_pickups: vec![], weapon_counter: config.spawn.weapon_spacing, seed, rng, start_x: spawn.x, level_starts: vec![], level: 0, distance: 0.0, score: 0, arena: None, boss: None, next_boss_distance: config.boss.first_distance, death: None, config };
+        world.add_levels(levels);
+        world
+    }
+
+    // Lay the authored levels down one after another, the endless terrain picks up where the last one ends
+    fn add_levels(&mut self, levels: &[Level]) {
+        let mut start_x = 0.0;
+        for level in levels {
+            self.level_starts.push(start_x);
+            for item in level.blocks.iter() {
+                self.blocks.push(Block::new(Rectangle::new(start_x + item.x, item.y, COLUMN_WIDTH, COLUMN_WIDTH), item.image, item.material));
+            }
+            for item in level.enemies.iter() {
+                self.spawn_enemy(item.kind, start_x + item.x, item.y);
+            }
+            for item in level.powerups.iter() {
+                self.powerups.push(Powerup { x: start_x + item.x, y: item.y, image: Sprite::Powerup });
+            }
+            start_x += level.width();
+            self.last_ground_y = level.exit_y().unwrap_or(self.last_ground_y);
+        }
+        self.block_id = start_x / COLUMN_WIDTH;
+    }
+
+    pub fn randomly_generate_background(rng: &mut StdRng, offset_x: f64) -> Background {
+        let num = rng.gen_range(0..100);
+        let background_image;
+        if num < 33 {
+            background_image = Sprite::Background1;
+        } else if num < 66 {
+            background_image = Sprite::Background2;
+        } else {
+            background_image = Sprite::Background3;
+        }
+        let num = rng.gen_range(0..100);
+        let rotation: f64;
+        if num < 25 {
+            rotation = 0.0;
+        } else if num < 50 {
+            rotation = PI*0.5;
+        } else if num < 75 {
+            rotation = PI;
+        } else {
+            rotation = PI*1.5;
+        }
+        Background::new(rng.gen_range(640..1280) as f64 + offset_x, rng.gen_range(-120..240) as f64, background_image, rotation)
+    }
+
+    pub fn randomly_generate_powerups(rng: &mut StdRng, offset_x: f64) -> Powerup {
+        Powerup { x: rng.gen_range(640..1280) as f64 + offset_x, y: rng.gen_range(220..300) as f64, image: Sprite::Powerup }
+    }
+
+    pub fn randomly_generate_enemy(rng: &mut StdRng, offset_x: f64, config: &EnemiesConfig) -> Enemy {
+        let stats = config.stats(EnemyKind::Turret);
+        Enemy::new(EnemyKind::Turret, rng.gen_range(640..1280) as f64 + offset_x, 64.0, stats.fire_cooldown, stats.health)
+    }
+
+    pub fn randomly_generate_weapon(rng: &mut StdRng, offset_x: f64) -> WeaponPickup {
+        //ANY WEAPON BUT THE BLASTER, WHICH THE PLAYER ALWAYS HAS
+        let weapon = Weapon::ALL[rng.gen_range(1..Weapon::ALL.len())];
+        WeaponPickup { x: rng.gen_range(640..1280) as f64 + offset_x, y: rng.gen_range(220..300) as f64, weapon }
+    }
+
+    fn spawn_enemy(&mut self, kind: EnemyKind, x: f64, y: f64) {
+        let stats = self.config.enemies.stats(kind);
+        self.enemies.push(Enemy::new(kind, x, y, stats.fire_cooldown, stats.health));
+    }
+
+    // Advance the simulation by `dt` seconds using the sampled input
+    pub fn step(&mut self, input: InputFrame, dt: f64) -> Vec<GameEvent> {
+        let mut events: Vec<GameEvent> = vec![];
+        if self.death.is_some() {
+            return events;
+        }
+        self.remember_positions();
+
+        //<KEYSTROKES>
+        //MOVEMENT LEFT-RIGHT WITH SPRINT
+        let mut target_speed = 0.0;
+        if input.right {
+            self.player.direction = 1.0;
+            if input.run {
+                target_speed = self.config.player.walk_speed * self.player.direction * self.config.player.running_rate;
+            } else {
+                target_speed = self.config.player.walk_speed * self.player.direction;
+            }
+        } else if input.left {
+            self.player.direction = -1.0;
+            if input.run {
+                target_speed = self.config.player.walk_speed * self.player.direction * self.config.player.running_rate;
+            } else {
+                target_speed = self.config.player.walk_speed * self.player.direction;
+            }
+        }
+
+        //AIR DASH, A BURST OF SPEED THAT IGNORES GRAVITY, ONCE PER JUMP
+        let abilities = self.config.abilities;
+        if input.dash && self.player.abilities.dash && self.player.can_dash && !self.player.standing {
+            self.player.can_dash = false;
+            self.player.dash_timer = abilities.dash_time;
+            self.player.jumping = false;
+            events.push(GameEvent::Dashed);
+        }
+
+        self.player.invulnerable_timer = (self.player.invulnerable_timer - dt).max(0.0);
+        if self.player.dash_timer > 0.0 {
+            self.player.dash_timer -= dt;
+            self.player.horizontal_speed = abilities.dash_speed * self.player.direction;
+            self.player.vertical_speed = 0.0;
+        } else if self.player.knockback_timer > 0.0 {
+            //KNOCKBACK, THE HIT CARRIES THE PLAYER AND THE INPUT IS IGNORED FOR A MOMENT
+            self.player.knockback_timer -= dt;
+        } else {
+            //ACCELERATION AND FRICTION, SCALED BY THE SURFACE UNDERFOOT OR BY THE AIR CONTROL
+            let (grip, top_speed) = match self.player.surface {
+                Some(material) => (self.config.surface.grip(material), self.config.surface.speed(material)),
+                None => (self.config.player.air_control, 1.0),
+            };
+            target_speed *= top_speed;
+            let speeding_up = target_speed.abs() > self.player.horizontal_speed.abs() && target_speed * self.player.horizontal_speed >= 0.0;
+            let rate = if speeding_up { self.config.player.acceleration } else { self.config.player.deceleration } * grip * dt;
+            self.player.horizontal_speed += (target_speed - self.player.horizontal_speed).clamp(-rate, rate);
+        }
+
+        //WALL SLIDE, PUSHING AGAINST A WALL IN THE AIR SLOWS THE FALL
+        let against_wall = (self.player.collides_left && input.left) || (self.player.collides_right && input.right);
+        let wall_sliding = self.player.abilities.wall_jump && against_wall && !self.player.standing;
+
+        //JUMP TRIGGER, A PRESS SHORTLY BEFORE LANDING OR SHORTLY AFTER WALKING OFF AN EDGE STILL COUNTS
+        self.player.jump_buffer_timer = (self.player.jump_buffer_timer - dt).max(0.0);
+        let can_jump = self.player.standing || self.player.coyote_timer > 0.0;
+        let wants_jump = input.jump_pressed || self.player.jump_buffer_timer > 0.0;
+        if can_jump && wants_jump {
+            self.player.vertical_speed = -self.config.player.jump_speed;
+            self.player.standing = false;
+            self.player.jumping = true;
+            self.player.coyote_timer = 0.0;
+            self.player.jump_buffer_timer = 0.0;
+            events.push(GameEvent::Jumped);
+        } else if input.jump_pressed && wall_sliding {
+            //WALL JUMP, KICKS THE PLAYER UP AND AWAY FROM THE WALL
+            self.player.direction = if self.player.collides_left { 1.0 } else { -1.0 };
+            self.player.horizontal_speed = abilities.wall_jump_push * self.player.direction;
+            self.player.vertical_speed = -self.config.player.jump_speed;
+            self.player.jumping = true;
+            events.push(GameEvent::WallJumped);
+        } else if input.jump_pressed && self.player.abilities.double_jump && self.player.air_jumps > 0 {
+            self.player.air_jumps -= 1;
+            self.player.vertical_speed = -self.config.player.jump_speed;
+            self.player.jumping = true;
+            self.player.dash_timer = 0.0;
+            events.push(GameEvent::Jumped);
+        } else if input.jump_pressed {
+            self.player.jump_buffer_timer = self.config.player.jump_buffer_time;
+        }
+
+        //JUMP CUT, LETTING GO OF THE KEY EARLY ENDS THE JUMP LOWER
+        if self.player.jumping && !input.jump_held && self.player.vertical_speed < 0.0 {
+            self.player.vertical_speed *= self.config.player.jump_cut;
+            self.player.jumping = false;
+        }
+
+        //SHOOT TRIGGER, HOLDING THE KEY FIRES AS FAST AS THE WEAPON ALLOWS
+        self.player.fire_timer = (self.player.fire_timer - dt).max(0.0);
+        if (input.shoot || input.shoot_held) && self.player.fire_timer <= 0.0 {
+            self.fire_weapon(&mut events);
+        }
+        //</KEYSTROKES>
+
+        //<GRAVITY>
+        if self.player.dash_timer <= 0.0 {
+            self.player.vertical_speed += self.config.player.gravity * dt;
+        }
+        //</GRAVITY>
+
+        //<LIMIT THE FALLING SPEED>
+        let max_fall_speed = if wall_sliding { abilities.wall_slide_speed } else { self.config.player.max_fall_speed };
+        if self.player.vertical_speed > max_fall_speed {
+            self.player.vertical_speed = max_fall_speed;
+        }
+        //</LIMIT THE FALLING SPEED>
+
+
+        //<APPLY THE MOVEMENT>
+        //SWEEP THE HITBOX THROUGH THE BLOCKS SO NO SPEED CAN CARRY THE PLAYER THROUGH ONE
+        let previous_x = self.player.pos_x;
+        let motion = self.player.collider().move_and_collide(
+            self.player.horizontal_speed * dt, self.player.vertical_speed * dt, self.blocks.iter().map(|item| &item.rect)
+        );
+        self.player.move_to(&motion.rect);
+        //THE ARENA HOLDS THE PLAYER IN UNTIL THE BOSS IS BEATEN, HOWEVER HIGH THEY GET OVER ITS WALLS
+        if let (Some(arena), Some(_)) = (&self.arena, &self.boss) {
+            let x = motion.rect.x.clamp(arena.x, arena.x + SCREEN_WIDTH - motion.rect.width);
+            if x != motion.rect.x {
+                self.player.move_to(&Rectangle { x, ..motion.rect });
+                self.player.horizontal_speed = 0.0;
+            }
+        }
+        self.player.collides_left = motion.contacts.left;
+        self.player.collides_right = motion.contacts.right;
+        if (self.player.collides_left && self.player.horizontal_speed < 0.0) || (self.player.collides_right && self.player.horizontal_speed > 0.0) {
+            self.player.horizontal_speed = 0.0;
+        }
+        self.player.standing = motion.contacts.floor;
+        self.player.surface = motion.ground.map(|index| self.blocks[index].material);
+        if self.player.standing && self.player.vertical_speed > 0.0 {
+            self.player.vertical_speed = 0.0;
+        }
+        if self.player.standing {
+            self.player.jumping = false;
+            self.player.air_jumps = 1;
+            self.player.can_dash = true;
+            self.player.coyote_timer = self.config.player.coyote_time;
+            self.player.checkpoint = (self.player.pos_x, self.player.pos_y);
+        } else {
+            self.player.coyote_timer = (self.player.coyote_timer - dt).max(0.0);
+        }
+        //HITTING THE UNDERSIDE OF A BLOCK ENDS THE JUMP
+        if motion.contacts.ceiling && self.player.vertical_speed < 0.0 {
+            self.player.vertical_speed = 0.0;
+            self.player.jumping = false;
+            events.push(GameEvent::HeadBump);
+        }
+
+        let distance = self.player.pos_x - previous_x;
+        self.distance = self.distance.max(self.player.pos_x - self.start_x);
+        self.background_counter -= distance;
+        self.enemy_counter -= distance;
+        self.powerup_counter -= distance;
+        self.weapon_counter -= distance;
+        self.speed_counter -= distance;
+        for item in self.enemies.iter_mut() {
+            item.cooldown -= distance;
+        }
+        //</APPLY THE MOVEMENT>
+
+        //ENTERING THE NEXT AUTHORED LEVEL
+        while self.level_starts.get(self.level + 1).is_some_and(|start| self.player.pos_x >= *start) {
+            self.level += 1;
+            events.push(GameEvent::LevelEntered(self.level));
+        }
+
+        //UNLOCK THE ABILITIES EARNED BY THE DISTANCE COVERED
+        for ability in Ability::ALL {
+            if !self.player.abilities.has(ability) && self.distance >= abilities.unlock_distance(ability) {
+                self.player.abilities.set(ability, true);
+                events.push(GameEvent::AbilityUnlocked(ability));
+            }
+        }
+
+        self.update_boss(dt * self.speed, &mut events);
+
+        //<FOLLOW THE PLAYER WITH THE CAMERA>
+        let previous_camera_x = self.camera.x;
+        self.camera.follow(self.player.pos_x, self.player.pos_y, dt);
+        //THE WALL IS PINNED TO THE SCREEN, SO IT SCROLLS ALONG WITH THE CAMERA
+        self.wall_x += self.camera.x - previous_camera_x;
+        //</FOLLOW THE PLAYER WITH THE CAMERA>
+
+
+        //THE WALL MOVEMENT
+        let wall = self.config.wall;
+        if self.wall_x - self.player.pos_x + wall.acceleration_distance > 0.0 {
+            self.wall_speed = wall.accelerated_speed;
+        } else {
+            self.wall_speed = wall.speed;
+        }
+
+        if self.speed < 1.0 {
+            self.wall_speed = -wall.pushback_speed;
+        }
+
+        //THE WALL WAITS FOR THE BOSS FIGHT TO BE OVER
+        if self.boss.is_some() {
+            self.wall_speed = 0.0;
+        }
+
+        //THE WALL "EATING" MECHANIQUE
+        if -(self.wall_x - self.player.pos_x + wall.acceleration_distance) < -3000.0 {
+            self.wall_speed = 0.0;
+        } else {
+            self.wall_x += self.wall_speed * dt * self.speed;
+        }
+
+
+        //WORLD GENERATION, ONE CHUNK AT A TIME AHEAD OF THE CAMERA, PAUSED FROM A BOSS ARENA UNTIL THE BOSS IS BEATEN
+        while self.arena.is_none() && COLUMN_WIDTH * self.block_id < self.camera.x + GENERATION_LEAD {
+            if COLUMN_WIDTH * self.block_id - self.start_x >= self.next_boss_distance {
+                self.arena = Some(Arena { x: COLUMN_WIDTH * self.block_id, ground_y: self.last_ground_y, walls: vec![], camera_limits: (None, None) });
+                let chunk = generator::arena(&mut self.rng, &self.config, self.last_ground_y);
+                self.add_chunk(chunk);
+                continue;
+            }
+            let difficulty = generator::difficulty(&self.config, self.distance);
+            let chunk = generator::next_chunk(&mut self.rng, &self.config, self.last_ground_y, difficulty);
+            self.add_chunk(chunk);
+        }
+
+        //MOVE BULLETS
+        for item in self.bullets.iter_mut() {
+            if item.previous.is_some() {
+                item.previous = Some(item.hitbox());
+            }
+            item.vertical_speed += item.gravity * dt;
+            item.x += item.horizontal_speed * dt;
+            item.y += item.vertical_speed * dt;
+            item.lifetime -= dt;
+        }
+        self.update_enemies(dt * self.speed);
+        self.collide_bullets(&mut events);
+
+        //GENERATE BACKGRONUDS
+        if self.background_counter <= 0.0 {
+            let offset_x = self.player.pos_x;
+            self.backgrounds.push(World::randomly_generate_background(&mut self.rng, offset_x));
+            self.background_counter = self.config.spawn.background_spacing;
+        }
+
+        //GENERATE ENEMIES, NOT WHILE A BOSS ARENA IS AHEAD
+        if self.enemy_counter <= 0.0 && self.arena.is_none() {
+            let offset_x = self.player.pos_x;
+            self.enemies.push(World::randomly_generate_enemy(&mut self.rng, offset_x, &self.config.enemies));
+            self.enemy_counter = self.config.spawn.enemy_spacing;
+        }
+
+        //GENERATE POWERUPS, NOT WHILE A BOSS ARENA IS AHEAD
+        if self.powerup_counter <= 0.0 && self.arena.is_none() {
+            let offset_x = self.player.pos_x;
+            self.powerups.push(World::randomly_generate_powerups(&mut self.rng, offset_x));
+            self.powerup_counter = self.config.spawn.powerup_spacing;
+        }
+
+        //GENERATE WEAPONS, NOT WHILE A BOSS ARENA IS AHEAD
+        if self.weapon_counter <= 0.0 && self.arena.is_none() {
+            let offset_x = self.player.pos_x;
+            self.weapon_pickups.push(World::randomly_generate_weapon(&mut self.rng, offset_x));
+            self.weapon_counter = self.config.spawn.weapon_spacing;
+        }
+
+        let player_collider = self.player.collider();
+
+        //PICK UP A WEAPON, ANOTHER ONE OF THE SAME KIND ADDS TO ITS AMMO
+        if let Some(i) = self.weapon_pickups.iter().position(|item| player_collider.intersects(&item.hitbox())) {
+            let weapon = self.weapon_pickups.remove(i).weapon;
+            let ammo = self.config.weapons.stats(weapon).ammo;
+            self.player.ammo = if self.player.weapon == weapon { self.player.ammo + ammo } else { ammo };
+            self.player.weapon = weapon;
+            events.push(GameEvent::WeaponCollected(weapon));
+        }
+
+        //COLLIDE WITH THE POWERUP
+        for item in self.powerups.iter_mut() {
+            let rect = Rectangle::new(item.x, item.y, 32.0, 32.0);
+            if player_collider.intersects(&rect) {
+                self.speed = self.config.powerup.slow_factor;
+                self.speed_counter = self.config.powerup.slow_distance;
+                self.clear_powerups = true;
+            }
+        }
+
+        //COLLECT POWERUPS
+        if self.clear_powerups {
+            self.clear_powerups = false;
+            self.powerups.clear();
+            events.push(GameEvent::PowerupCollected);
+        }
+
+        //RESET THE SPEED OF THE WORLD
+        if self.speed_counter <= 0.0 {
+            self.speed_counter = 0.0;
+            self.speed = 1.0;
+        }
+
+        //COLLIDE WITH THE ENEMY BULLET, THE BULLET IS SPENT ON THE HIT
+        if let Some(i) = self.enemy_bullets.iter().position(|item| player_collider.intersects(&item.hitbox())) {
+            if self.hurt(self.enemy_bullets[i].x, DeathCause::Shot, &mut events) {
+                self.enemy_bullets.remove(i);
+            }
+            if self.death.is_some() {
+                return events;
+            }
+        }
+
+        //RUNNING INTO AN ENEMY THAT HURTS ON CONTACT
+        let enemies = self.config.enemies;
+        if let Some(enemy) = self.enemies.iter().find(|item| enemies.stats(item.kind).contact_damage && player_collider.intersects(&item.hitbox())) {
+            let hitbox = enemy.hitbox();
+            self.hurt(hitbox.x + hitbox.width / 2.0, DeathCause::Touched, &mut events);
+            if self.death.is_some() {
+                return events;
+            }
+        }
+
+        //RUNNING INTO THE BOSS
+        if let Some(hitbox) = self.boss.as_ref().map(Boss::hitbox).filter(|hitbox| player_collider.intersects(hitbox)) {
+            self.hurt(hitbox.x + hitbox.width / 2.0, DeathCause::Touched, &mut events);
+            if self.death.is_some() {
+                return events;
+            }
+        }
+
+        //GENERATE ENEMY BULLET, AN ATTACK STARTS ONCE THE COOLDOWN IS OVER AND ENEMIES WITHOUT A COOLDOWN NEVER FIRE
+        let mut shots = vec![];
+        for item in self.enemies.iter_mut() {
+            let stats = enemies.stats(item.kind);
+            if stats.fire_cooldown > 0.0 && item.cooldown <= 0.0 && item.sees(&player_collider, stats.sight) {
+                item.burst.start(stats.pattern.burst);
+                item.cooldown = stats.fire_cooldown;
+            }
+            if item.burst.fire(dt * self.speed, stats.pattern.burst_interval) {
+                shots.push((item.muzzle(), stats.pattern));
+            }
+        }
+        for ((x, y), pattern) in shots {
+            self.fire_pattern(x, y, &pattern);
+            events.push(GameEvent::EnemyFired);
+        }
+
+        //MOVE ENEMY BULLET
+        let (target_x, target_y) = (player_collider.x + player_collider.width / 2.0, player_collider.y + player_collider.height / 2.0);
+        for item in self.enemy_bullets.iter_mut() {
+            item.advance(target_x, target_y, dt * self.speed);
+        }
+
+        self.despawn();
+
+        //THE WALL HAS EATEN THE PLAYER
+        if self.is_eaten() {
+            self.die(DeathCause::Wall, &mut events);
+        } else if self.player.pos_y > KILL_PLANE_Y {
+            self.lose_life(DeathCause::Fell, &mut events);
+        }
+
+        events
+    }
+
+    //<ENEMY BEHAVIOUR>
+    // Move every enemy the way its kind behaves
+    fn update_enemies(&mut self, dt: f64) {
+        let player = self.player.collider();
+        let (behaviour, player_config) = (self.config.enemy, self.config.player);
+        for enemy in self.enemies.iter_mut() {
+            let stats = self.config.enemies.stats(enemy.kind);
+            let hitbox = enemy.hitbox();
+            let to_player_x = (player.x + player.width / 2.0) - (hitbox.x + hitbox.width / 2.0);
+            let to_player_y = (player.y + player.height / 2.0) - (hitbox.y + hitbox.height / 2.0);
+            let sees_player = enemy.sees(&player, stats.sight);
+            enemy.age += dt;
+
+            let mut horizontal_speed = 0.0;
+            match enemy.kind {
+                //TURRETS STAY WHERE THEY ARE
+                EnemyKind::Turret => (),
+                //FLYERS HEAD FOR THE PLAYER ONCE THEY SEE THEM, BOBBING AROUND THEIR HEIGHT
+                EnemyKind::Flyer => {
+                    if sees_player && to_player_x.abs() > hitbox.width / 2.0 {
+                        enemy.direction = to_player_x.signum();
+                        horizontal_speed = stats.speed * enemy.direction;
+                    }
+                    enemy.x += horizontal_speed * dt;
+                    enemy.y = enemy.home_y + (enemy.age * behaviour.bob_rate).sin() * behaviour.bob_height;
+                },
+                //WALKERS KEEP PACING
+                EnemyKind::Walker => horizontal_speed = stats.speed * enemy.direction,
+                //CHARGERS WAIT FOR THE PLAYER TO COME ALONG THEIR FLOOR, RUSH AT THEM, THEN REST
+                EnemyKind::Charger => {
+                    enemy.state = match enemy.state {
+                        EnemyState::Idle if sees_player && to_player_y.abs() < COLUMN_WIDTH => {
+                            enemy.direction = if to_player_x < 0.0 { -1.0 } else { 1.0 };
+                            EnemyState::Charging(behaviour.charge_time)
+                        },
+                        EnemyState::Charging(time) if time > dt => EnemyState::Charging(time - dt),
+                        EnemyState::Charging(_) => EnemyState::Resting(behaviour.rest_time),
+                        EnemyState::Resting(time) if time > dt => EnemyState::Resting(time - dt),
+                        EnemyState::Resting(_) => EnemyState::Idle,
+                        state => state,
+                    };
+                    if let EnemyState::Charging(_) = enemy.state {
+                        horizontal_speed = stats.speed * enemy.direction;
+                    }
+                },
+                //DEVILS CHASE THE PLAYER ONCE THEY SEE THEM
+                EnemyKind::Devil => {
+                    if sees_player && to_player_x.abs() > hitbox.width / 4.0 {
+                        enemy.direction = if to_player_x < 0.0 { -1.0 } else { 1.0 };
+                        horizontal_speed = stats.speed * enemy.direction;
+                    }
+                },
+            }
+
+            //WALKING ENEMIES FALL ONTO THE BLOCKS AND NEVER STEP OFF THEIR EDGES
+            if enemy.kind.walks() {
+                enemy.vertical_speed = (enemy.vertical_speed + player_config.gravity * dt).min(player_config.max_fall_speed);
+                let motion = hitbox.move_and_collide(horizontal_speed * dt, enemy.vertical_speed * dt, self.blocks.iter().map(|block| &block.rect));
+                enemy.x = motion.rect.x;
+                enemy.y = motion.rect.y;
+                if motion.contacts.floor {
+                    enemy.vertical_speed = 0.0;
+                }
+                let front_x = if enemy.direction > 0.0 { motion.rect.right() } else { motion.rect.x - 1.0 };
+                let ahead = Rectangle::new(front_x, motion.rect.bottom(), 1.0, 2.0);
+                let at_edge = motion.contacts.floor && !self.blocks.iter().any(|block| block.rect.intersects(&ahead));
+                let blocked = (motion.contacts.left && enemy.direction < 0.0) || (motion.contacts.right && enemy.direction > 0.0);
+                if enemy.kind == EnemyKind::Devil {
+                    // A devil jumps up a step or across a gap in its way, and up to a player standing above it
+                    let player_above = to_player_y < -hitbox.height / 2.0 && to_player_x.abs() < COLUMN_WIDTH;
+                    if motion.contacts.floor && horizontal_speed != 0.0 && (at_edge || blocked || player_above) {
+                        enemy.vertical_speed = -behaviour.devil_jump_speed;
+                    }
+                } else if at_edge || blocked {
+                    match enemy.state {
+                        EnemyState::Charging(_) => enemy.state = EnemyState::Resting(behaviour.rest_time),
+                        _ if enemy.kind == EnemyKind::Walker => enemy.direction = -enemy.direction,
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+    //</ENEMY BEHAVIOUR>
+
+    //<BOSS FIGHT>
+    // Start the fight once the player reaches the middle of the arena, then move the boss and let it attack
+    fn update_boss(&mut self, dt: f64, events: &mut Vec<GameEvent>) {
+        let Some(arena) = &self.arena else {
+            return;
+        };
+        let Some(boss) = self.boss.as_mut() else {
+            if self.player.pos_x >= arena.x + SCREEN_WIDTH / 2.0 {
+                self.start_boss_fight(events);
+            }
+            return;
+        };
+        let config = self.config.boss;
+        boss.age += dt;
+        boss.cooldown -= dt;
+
+        //IT DRIFTS FROM ONE SIDE OF THE SCREEN TO THE OTHER, FASTER IN EVERY PHASE
+        let (left, right) = (arena.x + BOSS_MARGIN, arena.x + SCREEN_WIDTH - BOSS_SIZE - BOSS_MARGIN);
+        boss.x += config.sway_speed * (1.0 + boss.phase as f64 * 0.5) * boss.direction * dt;
+        if boss.x < left {
+            boss.x = left;
+            boss.direction = 1.0;
+        } else if boss.x > right {
+            boss.x = right;
+            boss.direction = -1.0;
+        }
+        boss.y = arena.ground_y - BOSS_HOVER_HEIGHT + (boss.age * 2.0).sin() * BOSS_BOB_HEIGHT;
+
+        //EVERY PHASE HAS AN ATTACK OF ITS OWN, LATER PHASES ATTACK MORE OFTEN
+        let pattern = config.patterns[boss.phase as usize % config.patterns.len()];
+        if boss.cooldown <= 0.0 {
+            boss.cooldown = config.fire_cooldown / (1.0 + boss.phase as f64 * 0.5);
+            boss.burst.start(pattern.burst);
+        }
+        if boss.burst.fire(dt, pattern.burst_interval) {
+            let (x, y) = (boss.x + BOSS_SIZE / 2.0 - 16.0, boss.y + BOSS_SIZE / 2.0 - 16.0);
+            self.fire_pattern(x, y, &pattern);
+            events.push(GameEvent::EnemyFired);
+        }
+    }
+
+    // Shut the player in the arena with a fresh boss, and hold the camera still over it
+    fn start_boss_fight(&mut self, events: &mut Vec<GameEvent>) {
+        let Some(arena) = self.arena.as_mut() else {
+            return;
+        };
+        arena.camera_limits = (self.camera.min_x, self.camera.max_x);
+        self.camera.min_x = Some(arena.x);
+        self.camera.max_x = Some(arena.x);
+
+        //A WALL JUST OUT OF VIEW ON EITHER SIDE
+        for x in [arena.x - COLUMN_WIDTH, arena.x + SCREEN_WIDTH] {
+            for i in 1..=ARENA_WALL_HEIGHT {
+                let rect = Rectangle::new(x, arena.ground_y - COLUMN_WIDTH * i as f64, COLUMN_WIDTH, COLUMN_WIDTH);
+                if !self.blocks.iter().any(|block| block.rect == rect) {
+                    self.blocks.push(Block::new(rect, Sprite::Block, Material::Normal));
+                    arena.walls.push(rect);
+                }
+            }
+        }
+
+        //THE BOSS HAS THE ARENA TO ITSELF
+        self.enemies.clear();
+        self.enemy_bullets.clear();
+        let config = self.config.boss;
+        self.boss = Some(Boss::new(arena.x + (SCREEN_WIDTH - BOSS_SIZE) / 2.0, arena.ground_y - BOSS_HOVER_HEIGHT, config.health, config.phases, config.fire_cooldown));
+        events.push(GameEvent::BossAppeared);
+    }
+
+    // Open the arena back up and let the run go on towards the next milestone
+    fn end_boss_fight(&mut self, events: &mut Vec<GameEvent>) {
+        self.boss = None;
+        let Some(arena) = self.arena.take() else {
+            return;
+        };
+        self.blocks.retain(|block| !arena.walls.contains(&block.rect));
+        (self.camera.min_x, self.camera.max_x) = arena.camera_limits;
+        self.enemy_bullets.clear();
+        self.next_boss_distance += self.config.boss.interval;
+        self.score += self.config.boss.kill_score;
+        events.push(GameEvent::BossDefeated);
+    }
+    //</BOSS FIGHT>
+
+    // Keep where everything that moves is before the tick, so drawing can blend between two ticks
+    fn remember_positions(&mut self) {
+        for item in self.bullets.iter_mut() {
+            (item.last_x, item.last_y) = (item.x, item.y);
+        }
+        for item in self.enemy_bullets.iter_mut() {
+            (item.last_x, item.last_y) = (item.x, item.y);
+        }
+        for item in self.enemies.iter_mut() {
+            (item.last_x, item.last_y) = (item.x, item.y);
+        }
+        if let Some(boss) = &mut self.boss {
+            (boss.last_x, boss.last_y) = (boss.x, boss.y);
+        }
+    }
+
+    // Fire one shot of `pattern` from `(x, y)` at the player, its bullets fanned out around the aim
+    fn fire_pattern(&mut self, x: f64, y: f64, pattern: &BulletPattern) {
+        let player = self.player.collider();
+        let (to_x, to_y) = (player.x + player.width / 2.0 - x - 16.0, player.y + player.height / 2.0 - y - 16.0);
+        let aim = if pattern.gravity > 0.0 { lob_angle(to_x, to_y, pattern.speed, pattern.gravity) } else { to_y.atan2(to_x) };
+        for i in 0..pattern.count {
+            let angle = aim + (i as f64 - (pattern.count - 1) as f64 / 2.0) * pattern.spread.to_radians();
+            self.enemy_bullets.push(Enemy_Bullet {
+                x, y, horizontal_speed: angle.cos() * pattern.speed, vertical_speed: angle.sin() * pattern.speed, turn_rate: pattern.turn_rate.to_radians(), gravity: pattern.gravity,
+                image: Sprite::EnemyBullet, lifetime: self.config.enemy.bullet_lifetime, last_x: x, last_y: y
+            });
+        }
+    }
+
+    // Fire a shot of the current weapon, going back to the blaster once its ammo is spent
+    fn fire_weapon(&mut self, events: &mut Vec<GameEvent>) {
+        let stats = *self.config.weapons.stats(self.player.weapon);
+        let (aim, gravity) = match stats.projectile {
+            Projectile::Bullet => (0.0, 0.0),
+            Projectile::Grenade => (-GRENADE_LAUNCH_ANGLE, self.config.bullet.grenade_gravity),
+        };
+        for i in 0..stats.count {
+            let angle = aim + (i as f64 - (stats.count - 1) as f64 / 2.0) * stats.spread.to_radians();
+            let (x, y) = (self.player.pos_x + 32.0, self.player.pos_y + 16.0);
+            self.bullets.push(Bullet {
+                x, y, horizontal_speed: angle.cos() * stats.speed * self.player.direction, vertical_speed: angle.sin() * stats.speed,
+                gravity, projectile: stats.projectile, damage: stats.damage, pierce: stats.pierce, previous: None, lifetime: self.config.bullet.lifetime, last_x: x, last_y: y
+            });
+        }
+        self.player.fire_timer = 1.0 / stats.fire_rate;
+        events.push(GameEvent::Shot);
+
+        if stats.ammo > 0 {
+            self.player.ammo = self.player.ammo.saturating_sub(1);
+            if self.player.ammo == 0 {
+                self.player.weapon = Weapon::Blaster;
+                events.push(GameEvent::OutOfAmmo);
+            }
+        }
+    }
+
+    // A bullet stops at the first thing it hits: the boss or an enemy it damages unless it pierces them, a block, or an enemy bullet it shoots down
+    fn collide_bullets(&mut self, events: &mut Vec<GameEvent>) {
+        for bullet in self.bullets.iter_mut() {
+            let hitbox = bullet.hitbox();
+            if let Some(boss) = self.boss.as_mut().filter(|boss| boss.health > 0 && bullet.enters(&boss.hitbox())) {
+                bullet.spend();
+                boss.health = boss.health.saturating_sub(bullet.damage);
+                let phase = boss.phase_for_health();
+                if boss.health > 0 && phase > boss.phase {
+                    boss.phase = phase;
+                    events.push(GameEvent::BossPhase(phase));
+                } else if boss.health > 0 {
+                    events.push(GameEvent::EnemyHit);
+                }
+            } else if let Some(enemy) = self.enemies.iter_mut().find(|enemy| enemy.health > 0 && bullet.enters(&enemy.hitbox())) {
+                bullet.spend();
+                enemy.health = enemy.health.saturating_sub(bullet.damage);
+                if enemy.health == 0 {
+                    self.score += self.config.enemies.stats(enemy.kind).kill_score;
+                    events.push(GameEvent::EnemyKilled);
+                } else {
+                    events.push(GameEvent::EnemyHit);
+                }
+            } else if self.blocks.iter().any(|block| block.rect.intersects(&hitbox)) {
+                bullet.lifetime = 0.0;
+            } else if let Some(enemy_bullet) = self.enemy_bullets.iter_mut().find(|item| self.config.bullet.hits_enemy_bullets && item.lifetime > 0.0 && item.hitbox().intersects(&hitbox)) {
+                bullet.lifetime = 0.0;
+                enemy_bullet.lifetime = 0.0;
+                events.push(GameEvent::EnemyBulletShotDown);
+            }
+        }
+        self.bullets.retain(|item| item.lifetime > 0.0);
+        self.enemies.retain(|item| item.health > 0);
+        self.enemy_bullets.retain(|item| item.lifetime > 0.0);
+        if self.boss.as_ref().is_some_and(|boss| boss.health == 0) {
+            self.end_boss_fight(events);
+        }
+    }
+
+    // Take a hit from something at `from_x`, unless the player is still recovering from the last one
+    fn hurt(&mut self, from_x: f64, cause: DeathCause, events: &mut Vec<GameEvent>) -> bool {
+        if self.player.invulnerable_timer > 0.0 {
+            return false;
+        }
+        let health = self.config.health;
+        self.player.health = self.player.health.saturating_sub(1);
+        self.player.invulnerable_timer = health.invulnerable_time;
+        self.player.knockback_timer = health.knockback_time;
+        let away = if from_x > self.player.pos_x { -1.0 } else { 1.0 };
+        self.player.horizontal_speed = away * health.knockback_speed;
+        self.player.vertical_speed = -health.knockback_lift;
+        self.player.standing = false;
+        self.player.jumping = false;
+        self.player.dash_timer = 0.0;
+        events.push(GameEvent::PlayerHurt);
+        if self.player.health == 0 {
+            self.lose_life(cause, events);
+        }
+        true
+    }
+
+    // The run only ends with the last life, otherwise the player is healed and, after a fall, put back on the last ground stood on
+    fn lose_life(&mut self, cause: DeathCause, events: &mut Vec<GameEvent>) {
+        self.player.lives = self.player.lives.saturating_sub(1);
+        if self.player.lives == 0 {
+            self.die(cause, events);
+            return;
+        }
+        self.player.health = self.config.health.max_health;
+        self.player.invulnerable_timer = self.config.health.invulnerable_time;
+        if cause == DeathCause::Fell {
+            (self.player.pos_x, self.player.pos_y) = self.player.checkpoint;
+            self.player.horizontal_speed = 0.0;
+            self.player.vertical_speed = 0.0;
+            self.player.knockback_timer = 0.0;
+        }
+        events.push(GameEvent::LifeLost(cause));
+    }
+
+    fn die(&mut self, cause: DeathCause, events: &mut Vec<GameEvent>) {
+        self.death = Some(cause);
+        events.push(GameEvent::PlayerDied(cause));
+    }
+
+    //<DESPAWN>
+    // Drop whatever the wall has passed or the camera left far behind, and spent or stray bullets
+    fn despawn(&mut self) {
+        let behind = self.wall_x.max(self.camera.x - DESPAWN_DISTANCE);
+        let camera = self.camera;
+        let on_screen = |x: f64, y: f64| {
+            let (x, y) = camera.to_screen(x, y);
+            x > -SCREEN_WIDTH && x < 2.0 * SCREEN_WIDTH && y > -SCREEN_HEIGHT && y < 2.0 * SCREEN_HEIGHT
+        };
+
+        self.blocks.retain(|item| item.rect.x + item.rect.width > behind);
+        self.backgrounds.retain(|item| item.x + SCREEN_WIDTH > behind);
+        self.powerups.retain(|item| item.x > behind);
+        self.weapon_pickups.retain(|item| item.x > behind);
+        self.enemies.retain(|item| item.x > behind && item.y < KILL_PLANE_Y);
+        self.bullets.retain(|item| item.lifetime > 0.0 && on_screen(item.x, item.y));
+        self.enemy_bullets.retain(|item| item.lifetime > 0.0 && on_screen(item.x, item.y));
+    }
+    //</DESPAWN>
+
+    pub fn entity_counts(&self) -> EntityCounts {
+        EntityCounts {
+            blocks: self.blocks.len(),
+            bullets: self.bullets.len(),
+            backgrounds: self.backgrounds.len(),
+            powerups: self.powerups.len(),
+            enemies: self.enemies.len(),
+            enemy_bullets: self.enemy_bullets.len(),
+            weapon_pickups: self.weapon_pickups.len()
+        }
+    }
+
+    // Lay a chunk down right after the terrain generated so far
+    fn add_chunk(&mut self, chunk: Chunk) {
+        let start_x = COLUMN_WIDTH * self.block_id;
+        for (i, column) in chunk.columns.iter().enumerate() {
+            let x = start_x + COLUMN_WIDTH * i as f64;
+            //FILL THE GROUND DOWN TO THE BOTTOM OF THE SCREEN
+            if let Some((top, material)) = column.ground {
+                let mut y = top;
+                while y < SCREEN_HEIGHT {
+                    let material = if y == top { material } else { Material::Normal };
+                    self.blocks.push(Block::new(Rectangle::new(x, y, COLUMN_WIDTH, COLUMN_WIDTH), Sprite::Block, material));
+                    y += COLUMN_WIDTH;
+                }
+            }
+            if let Some(y) = column.ceiling {
+                self.blocks.push(Block::new(Rectangle::new(x, y, COLUMN_WIDTH, COLUMN_WIDTH), Sprite::Block, Material::Normal));
+            }
+        }
+        for (i, y, kind) in chunk.enemies.iter() {
+            self.spawn_enemy(*kind, start_x + COLUMN_WIDTH * *i as f64 + 64.0, *y);
+        }
+        for (i, y) in chunk.powerups.iter() {
+            self.powerups.push(Powerup { x: start_x + COLUMN_WIDTH * *i as f64 + 48.0, y: *y, image: Sprite::Powerup });
+        }
+
+        self.block_id += chunk.columns.len() as f64;
+        self.last_ground_y = chunk.exit_y().unwrap_or(self.last_ground_y);
+        self.last_chunk = chunk.kind;
+    }
+
+    // Whether the wall has caught up with the player and covers the screen
+    fn is_eaten(&self) -> bool {
+        self.wall_x - self.player.pos_x + self.config.wall.eat_distance > 0.0
+    }
+}
+
+// The angle to throw at `speed` for gravity to bring the bullet down on a target `(dx, dy)` away, along the high arc,
+// or at 45 degrees when the target is out of reach
+fn lob_angle(dx: f64, dy: f64, speed: f64, gravity: f64) -> f64 {
+    let (reach, rise) = (dx.abs(), -dy);
+    let discriminant = speed.powi(4) - gravity * (gravity * reach * reach + 2.0 * rise * speed * speed);
+    let elevation = if discriminant >= 0.0 && reach > 0.0 { ((speed * speed + discriminant.sqrt()) / (gravity * reach)).atan() } else { PI / 4.0 };
+    if dx < 0.0 { PI + elevation } else { -elevation }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::LevelBlock;
+
+    const DT: f64 = 1.0 / 120.0;
+
+    // A floor of `columns` blocks with its top at y 320, the player dropped in at `spawn_x`
+    fn floor_level(columns: usize, spawn_x: f64) -> Level {
+        let blocks = (0..columns).map(|i| LevelBlock { x: COLUMN_WIDTH * i as f64, y: 320.0, material: Material::Normal, image: Sprite::Block }).collect();
+        Level { name: String::new(), spawn: Some(Point { x: spawn_x, y: 0.0 }), wall_start: None, exit_y: None, blocks, enemies: vec![], powerups: vec![] }
+    }
+
+    // A world on a floor with nothing around that could shoot or touch the player
+    fn quiet_world(columns: usize, spawn_x: f64) -> World {
+        let mut world = World::new(1, GameConfig::default(), &[floor_level(columns, spawn_x)]);
+        world.enemies.clear();
+        world
+    }
+
+    #[test]
+    fn player_lands_on_a_block() {
+        let mut world = quiet_world(10, 45.0);
+        for _ in 0..240 {
+            world.step(InputFrame::default(), DT);
+        }
+        assert!(world.player.standing);
+        assert_eq!(world.player.collider().bottom(), 320.0);
+    }
+
+    #[test]
+    fn wall_eats_a_player_standing_still() {
+        let mut world = quiet_world(10, 45.0);
+        let mut events = vec![];
+        for _ in 0..120 * 30 {
+            events = world.step(InputFrame::default(), DT);
+            if world.death.is_some() {
+                break;
+            }
+        }
+        assert_eq!(world.death, Some(DeathCause::Wall));
+        assert!(events.contains(&GameEvent::PlayerDied(DeathCause::Wall)));
+    }
+
+    #[test]
+    fn terrain_goes_on_after_a_level_without_blocks() {
+        let mut world = World::new(1, GameConfig::default(), &[floor_level(0, 45.0)]);
+        world.step(InputFrame::default(), DT);
+        assert!(!world.blocks.is_empty());
+        assert!(world.blocks.iter().all(|block| block.rect.y.is_finite()));
+    }
+
+    #[test]
+    fn spawning_far_into_a_level_does_not_feed_the_player_to_the_wall() {
+        let mut level = floor_level(30, 2000.0);
+        level.wall_start = Some(2000.0 + GameConfig::default().wall.start_x);
+        let mut world = World::new(1, GameConfig::default(), &[level]);
+        world.enemies.clear();
+        for _ in 0..120 {
+            let events = world.step(InputFrame::default(), DT);
+            assert!(!events.contains(&GameEvent::PlayerDied(DeathCause::Wall)));
+        }
+        assert_eq!(world.death, None);
+    }
+
+    // A world where the player has just started a boss fight in an arena laid on a long floor, the boss holding its fire
+    fn arena_world() -> World {
+        let mut world = quiet_world(40, 1280.0 + SCREEN_WIDTH / 2.0);
+        world.config.boss.fire_cooldown = f64::INFINITY;
+        world.arena = Some(Arena { x: 1280.0, ground_y: 320.0, walls: vec![], camera_limits: (None, None) });
+        world.wall_x = f64::NEG_INFINITY;
+        world.player.invulnerable_timer = f64::INFINITY;
+        world.start_boss_fight(&mut vec![]);
+        world
+    }
+
+    #[test]
+    fn arena_holds_the_player_in_however_they_jump() {
+        for right in [true, false] {
+            let mut world = arena_world();
+            for i in 0..120 * 20 {
+                let input = InputFrame { right, left: !right, run: true, jump_pressed: i % 15 == 0, jump_held: true, ..Default::default() };
+                world.step(input, DT);
+                let collider = world.player.collider();
+                assert!(world.boss.is_some());
+                assert!(collider.x >= 1280.0 && collider.right() <= 1280.0 + SCREEN_WIDTH, "escaped to x {} on tick {}", collider.x, i);
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_replays_the_same_run() {
+        let run = || {
+            let mut world = World::new(7, GameConfig::default(), &[]);
+            let mut events = vec![];
+            for i in 0..120 * 10 {
+                let input = InputFrame { right: true, run: i % 200 < 150, jump_pressed: i % 90 == 0, jump_held: i % 90 < 30, shoot: i % 40 == 0, ..Default::default() };
+                events.extend(world.step(input, DT));
+            }
+            let blocks: Vec<Rectangle> = world.blocks.iter().map(|block| block.rect).collect();
+            let enemies: Vec<(f64, f64, u32)> = world.enemies.iter().map(|enemy| (enemy.x, enemy.y, enemy.health)).collect();
+            format!("{:?} {:?} {:?} {:?} {} {} {:?}", world.player, blocks, enemies, events, world.score, world.distance, world.death)
+        };
+        assert_eq!(run(), run());
+    }
+}